@@ -0,0 +1,376 @@
+//! Procedural macros of the `sql-connect` crate.
+use std::ffi::{
+	CStr,
+	CString
+};
+use std::os::raw::{
+	c_char,
+	c_int
+};
+use std::path::{
+	Path,
+	PathBuf
+};
+use libsqlite3_sys as ffi;
+use proc_macro2::{
+	Span,
+	TokenStream
+};
+use quote::quote;
+use syn::{
+	parse::{
+		Parse,
+		ParseStream
+	},
+	Attribute,
+	Ident,
+	LitStr,
+	Token,
+	Visibility
+};
+
+/// Define a typed query, checked at compile time against a database schema.
+///
+/// ```ignore
+/// sql_connect::query!(pub UserById, "schema.sql", "SELECT id, name FROM user WHERE id = ?");
+/// ```
+///
+/// A relative schema path is resolved against the crate root (`CARGO_MANIFEST_DIR`),
+/// or against the `SQL_CONNECT_SCHEMA_ROOT` environment variable if it is set.
+/// It is either an SQL file, or a directory of migrations whose `.sql` files are run in name order.
+/// The schema is loaded into an in-memory SQLite database, against which the query is prepared.
+///
+/// This defines a struct with one public field per result column, named after the column.
+/// Field types are inferred from the declared column types, following the SQLite affinity rules:
+/// `i64` for `INT` types, `String` for `CHAR`, `CLOB` and `TEXT` types, `Vec<u8>` for `BLOB`,
+/// `f64` for `REAL`, `FLOA` and `DOUB` types, and `OwnedValue` otherwise (including expressions).
+/// Table columns are wrapped in an `Option`, unless declared `NOT NULL` or `INTEGER PRIMARY KEY`.
+/// If the query contains an outer join (`LEFT`, `RIGHT` or `FULL`), every table column is wrapped in an `Option`.
+///
+/// The struct implements `FromRow`, and provides a `prepare` function building a typed `Query`,
+/// whose parameters are an array of values with one item per statement parameter.
+#[proc_macro]
+pub fn query(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = syn::parse_macro_input!(input as QueryInput);
+	match expand(input) {
+		Ok(tokens) => tokens.into(),
+		Err(e) => e.to_compile_error().into()
+	}
+}
+
+struct QueryInput {
+	attrs: Vec<Attribute>,
+	vis: Visibility,
+	name: Ident,
+	schema: LitStr,
+	sql: LitStr
+}
+
+impl Parse for QueryInput {
+	fn parse(input: ParseStream) -> syn::Result<QueryInput> {
+		let attrs = input.call(Attribute::parse_outer)?;
+		let vis = input.parse()?;
+		let name = input.parse()?;
+		input.parse::<Token![,]>()?;
+		let schema = input.parse()?;
+		input.parse::<Token![,]>()?;
+		let sql = input.parse()?;
+		input.parse::<Option<Token![,]>>()?;
+
+		Ok(QueryInput {
+			attrs,
+			vis,
+			name,
+			schema,
+			sql
+		})
+	}
+}
+
+/// Result column of a query.
+struct Column {
+	name: String,
+	decltype: Option<String>,
+	nullable: bool
+}
+
+/// Parameters and columns of a query.
+struct Description {
+	parameter_count: usize,
+	columns: Vec<Column>
+}
+
+/// In-memory database holding the schema.
+struct Database {
+	handle: *mut ffi::sqlite3
+}
+
+impl Database {
+	fn new() -> Result<Database, String> {
+		unsafe {
+			let mut handle = std::ptr::null_mut();
+			let res = ffi::sqlite3_open_v2(b":memory:\0".as_ptr() as *const c_char, &mut handle, ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE, std::ptr::null());
+			let db = Database {
+				handle
+			};
+
+			if res == ffi::SQLITE_OK {
+				Ok(db)
+			} else {
+				Err(db.error_message())
+			}
+		}
+	}
+
+	fn error_message(&self) -> String {
+		unsafe {
+			CStr::from_ptr(ffi::sqlite3_errmsg(self.handle)).to_string_lossy().into_owned()
+		}
+	}
+
+	/// Run the given SQL script.
+	fn execute_script(&self, sql: &str) -> Result<(), String> {
+		let sql = CString::new(sql).map_err(|_| "schema contains a NUL character".to_string())?;
+		unsafe {
+			let mut message = std::ptr::null_mut();
+			let res = ffi::sqlite3_exec(self.handle, sql.as_ptr(), None, std::ptr::null_mut(), &mut message);
+			if res == ffi::SQLITE_OK {
+				Ok(())
+			} else if message.is_null() {
+				Err(self.error_message())
+			} else {
+				let error = CStr::from_ptr(message).to_string_lossy().into_owned();
+				ffi::sqlite3_free(message as *mut _);
+				Err(error)
+			}
+		}
+	}
+
+	/// Prepare the given query and describe its parameters and columns.
+	fn describe(&self, sql: &str) -> Result<Description, String> {
+		unsafe {
+			let mut stmt = std::ptr::null_mut();
+			let mut tail = std::ptr::null();
+			let res = ffi::sqlite3_prepare_v2(self.handle, sql.as_ptr() as *const c_char, sql.len() as c_int, &mut stmt, &mut tail);
+			if res != ffi::SQLITE_OK {
+				return Err(self.error_message())
+			}
+
+			if stmt.is_null() {
+				return Err("empty query".to_string())
+			}
+
+			let stmt = Statement(stmt);
+			let rest = sql.len() - (tail as usize - sql.as_ptr() as usize);
+			let mut next = std::ptr::null_mut();
+			let res = ffi::sqlite3_prepare_v2(self.handle, tail, rest as c_int, &mut next, std::ptr::null_mut());
+			if res != ffi::SQLITE_OK {
+				return Err(self.error_message())
+			}
+
+			if !next.is_null() {
+				ffi::sqlite3_finalize(next);
+				return Err("the query must consist of a single statement".to_string())
+			}
+
+			let outer_join = has_outer_join(sql);
+			let columns = (0..ffi::sqlite3_column_count(stmt.0)).map(|i| self.column(&stmt, i, outer_join)).collect::<Result<_, _>>()?;
+			Ok(Description {
+				parameter_count: ffi::sqlite3_bind_parameter_count(stmt.0) as usize,
+				columns
+			})
+		}
+	}
+
+	unsafe fn column(&self, stmt: &Statement, i: c_int, outer_join: bool) -> Result<Column, String> {
+		let name = optional_str(ffi::sqlite3_column_name(stmt.0, i)).ok_or_else(|| "unnamed column".to_string())?;
+		let decltype = optional_str(ffi::sqlite3_column_decltype(stmt.0, i));
+
+		let nullable = match (
+			optional_str(ffi::sqlite3_column_database_name(stmt.0, i)),
+			optional_str(ffi::sqlite3_column_table_name(stmt.0, i)),
+			optional_str(ffi::sqlite3_column_origin_name(stmt.0, i))
+		) {
+			(Some(_), Some(_), Some(_)) if outer_join => true,
+			(Some(database), Some(table), Some(column)) => {
+				let database = CString::new(database).unwrap();
+				let table = CString::new(table).unwrap();
+				let column = CString::new(column).unwrap();
+				let mut declared = std::ptr::null();
+				let mut collation = std::ptr::null();
+				let mut not_null = 0;
+				let mut primary_key = 0;
+				let mut autoinc = 0;
+				let res = ffi::sqlite3_table_column_metadata(self.handle, database.as_ptr(), table.as_ptr(), column.as_ptr(), &mut declared, &mut collation, &mut not_null, &mut primary_key, &mut autoinc);
+				if res != ffi::SQLITE_OK {
+					return Err(self.error_message())
+				}
+
+				// An `INTEGER PRIMARY KEY` column is an alias of the row id.
+				let rowid = primary_key != 0 && optional_str(declared).map(|t| t.eq_ignore_ascii_case("INTEGER")).unwrap_or(false);
+				not_null == 0 && !rowid
+			},
+			_ => true
+		};
+
+		Ok(Column {
+			name: name.to_string(),
+			decltype: decltype.map(str::to_string),
+			nullable
+		})
+	}
+}
+
+impl Drop for Database {
+	fn drop(&mut self) {
+		unsafe {
+			ffi::sqlite3_close(self.handle);
+		}
+	}
+}
+
+struct Statement(*mut ffi::sqlite3_stmt);
+
+impl Drop for Statement {
+	fn drop(&mut self) {
+		unsafe {
+			ffi::sqlite3_finalize(self.0);
+		}
+	}
+}
+
+unsafe fn optional_str<'a>(ptr: *const c_char) -> Option<&'a str> {
+	if ptr.is_null() {
+		None
+	} else {
+		CStr::from_ptr(ptr).to_str().ok()
+	}
+}
+
+/// Checks if the given query contains an outer join, whose columns may be `NULL`.
+fn has_outer_join(sql: &str) -> bool {
+	let words: Vec<_> = sql.split(|c: char| !c.is_alphanumeric() && c != '_').filter(|w| !w.is_empty()).map(str::to_uppercase).collect();
+	words.windows(2).any(|w| {
+		matches!(w[0].as_str(), "LEFT" | "RIGHT" | "FULL") && matches!(w[1].as_str(), "JOIN" | "OUTER")
+	})
+}
+
+/// List the schema files at the given path.
+///
+/// If the path is a directory, its `.sql` files are listed in name order.
+fn schema_files(path: &Path) -> Result<Vec<PathBuf>, String> {
+	if path.is_dir() {
+		let mut files = Vec::new();
+		for entry in std::fs::read_dir(path).map_err(|e| format!("unable to read `{}`: {}", path.display(), e))? {
+			let file = entry.map_err(|e| format!("unable to read `{}`: {}", path.display(), e))?.path();
+			if file.extension().map(|ext| ext == "sql").unwrap_or(false) {
+				files.push(file)
+			}
+		}
+
+		files.sort();
+		Ok(files)
+	} else {
+		Ok(vec![path.to_owned()])
+	}
+}
+
+/// Rust type of a column, following the SQLite type affinity rules.
+fn column_type(column: &Column) -> TokenStream {
+	let ty = match column.decltype.as_ref().map(|t| t.to_uppercase()) {
+		Some(t) if t.contains("INT") => quote!(i64),
+		Some(t) if t.contains("CHAR") || t.contains("CLOB") || t.contains("TEXT") => quote!(::std::string::String),
+		Some(t) if t.contains("BLOB") => quote!(::std::vec::Vec<u8>),
+		Some(t) if t.contains("REAL") || t.contains("FLOA") || t.contains("DOUB") => quote!(f64),
+		_ => return quote!(::sql_connect::OwnedValue)
+	};
+
+	if column.nullable {
+		quote!(::std::option::Option<#ty>)
+	} else {
+		ty
+	}
+}
+
+fn expand(input: QueryInput) -> syn::Result<TokenStream> {
+	let root = std::env::var("SQL_CONNECT_SCHEMA_ROOT")
+		.or_else(|_| std::env::var("CARGO_MANIFEST_DIR"))
+		.map_err(|_| syn::Error::new(Span::call_site(), "`CARGO_MANIFEST_DIR` is not defined"))?;
+	let schema_path = Path::new(&root).join(input.schema.value());
+	let schema_error = |e: String| syn::Error::new(input.schema.span(), e);
+
+	let db = Database::new().map_err(schema_error)?;
+	let files = schema_files(&schema_path).map_err(schema_error)?;
+	let mut includes = Vec::new();
+	for file in &files {
+		let script = std::fs::read_to_string(file).map_err(|e| schema_error(format!("unable to read `{}`: {}", file.display(), e)))?;
+		db.execute_script(&script).map_err(|e| schema_error(format!("error in `{}`: {}", file.display(), e)))?;
+
+		// Rebuild when the schema changes.
+		let file = file.to_string_lossy();
+		includes.push(quote!(const _: &str = include_str!(#file);))
+	}
+
+	let sql = input.sql.value();
+	let description = db.describe(&sql).map_err(|e| syn::Error::new(input.sql.span(), e))?;
+
+	let mut field_names = Vec::new();
+	for column in &description.columns {
+		let field: Ident = syn::parse_str(&column.name).map_err(|_| syn::Error::new(input.sql.span(), format!("column `{}` is not a valid field name, rename it with `AS`", column.name)))?;
+		if field_names.contains(&field) {
+			return Err(syn::Error::new(input.sql.span(), format!("duplicate column `{}`, rename it with `AS`", column.name)))
+		}
+
+		field_names.push(field)
+	}
+
+	let field_types: Vec<_> = description.columns.iter().map(column_type).collect();
+	let column_count = description.columns.len();
+	let parameter_count = description.parameter_count;
+
+	let QueryInput { attrs, vis, name, .. } = input;
+	Ok(quote! {
+		#(#attrs)*
+		#[derive(Clone, Debug, PartialEq)]
+		#vis struct #name {
+			#(pub #field_names: #field_types,)*
+		}
+
+		impl #name {
+			/// SQL text of the query.
+			pub const SQL: &'static str = #sql;
+
+			/// Prepare the query through the given connection.
+			pub fn prepare<'p, C: ::sql_connect::Connection>(connection: &mut C) -> ::sql_connect::Result<::sql_connect::Query<[::sql_connect::Value<'p>; #parameter_count], #name, C::Statement>> where C::Statement: ::sql_connect::StatementInfo {
+				connection.prepare_query(Self::SQL)
+			}
+		}
+
+		impl ::sql_connect::FromRow for #name {
+			fn from<'a, R: ::sql_connect::RowValues<'a>>(mut row: R) -> Self {
+				#name {
+					#(#field_names: ::sql_connect::FromValue::from(row.next().unwrap()),)*
+				}
+			}
+
+			fn try_from_row<'a, R: ::sql_connect::RowValues<'a>>(mut row: R) -> ::sql_connect::Result<Self> {
+				::std::result::Result::Ok(#name {
+					#(#field_names: {
+						let value = row.next().ok_or_else(|| ::sql_connect::ErrorKind::ColumnCountMismatch { expected: #column_count, found: row.column_count() }.err())?;
+						if !<#field_types as ::sql_connect::FromValue>::accepts(&value) {
+							return ::std::result::Result::Err(::sql_connect::ErrorKind::TypeMismatch.err())
+						}
+
+						::sql_connect::FromValue::from(value)
+					},)*
+				})
+			}
+
+			fn column_count() -> ::std::option::Option<usize> {
+				::std::option::Option::Some(#column_count)
+			}
+		}
+
+		#(#includes)*
+	})
+}
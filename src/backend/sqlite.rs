@@ -1,7 +1,11 @@
 use std::path::Path;
+use std::ops::Range;
 use std::marker::PhantomData;
 use std::fmt;
-use std::ffi::CString;
+use std::ffi::{
+	CStr,
+	CString
+};
 use std::os::raw::{
 	c_void,
 	c_char,
@@ -28,115 +32,122 @@ use crate::{
 	ErrorKind,
 	FromRow,
 	Value,
+	Execution,
+	ExecResult,
 	backoff::{
 		BackoffExt,
 		BackoffState
 	}
 };
 
+extern "C" {
+	// Not part of the bindings of the minimum SQLite version supported by `libsqlite3-sys`.
+	fn sqlite3_changes64(db: *mut ffi::sqlite3) -> ffi::sqlite3_int64;
+	fn sqlite3_total_changes64(db: *mut ffi::sqlite3) -> ffi::sqlite3_int64;
+}
+
+mod error;
+mod serialize;
+mod session;
+mod attach;
+mod pragma;
+mod introspection;
+mod trace;
+mod explain;
+mod status;
+
+pub use error::{
+	SqliteError,
+	Constraint,
+	DatabaseError
+};
+pub use session::*;
+pub use attach::*;
+pub use pragma::*;
+pub use introspection::*;
+pub use trace::*;
+pub use explain::*;
+pub use status::*;
+
+use error::{
+	check,
+	check_db
+};
+
 pub struct Connection {
 	handle: *mut ffi::sqlite3,
-	next_savepoint: usize
+	next_savepoint: usize,
+	attached: Vec<Attachment>,
+
+	/// Trace callback, which must live until the connection is closed.
+	trace: Option<Box<trace::TraceCallback>>
 }
 
 unsafe impl Send for Connection { }
 
-#[derive(Debug)]
-pub enum SqliteError {
-	Unknown,
-	Internal,
-	Perm,
-	Abort,
-	Busy,
-	Locked,
-	NoMem,
-	ReadOnly,
-	Interrupt,
-	IO,
-	Corrupt,
-	NotFound,
-	Full,
-	CantOpen,
-	Protocol,
-	Empty,
-	Schema,
-	TooBig,
-	Constraint,
-	Mismatch,
-	Misuse,
-	NoLFS,
-	Auth,
-	Format,
-	Range,
-	NotADB
+fn str_to_cstring(s: &str) -> Result<CString> {
+	CString::new(s).map_err(|_| ErrorKind::InvalidString(s.to_string()).err())
 }
 
-impl fmt::Display for SqliteError {
-	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "{:?}", self)
+/// Copy the content of an SQLite dynamically typed value.
+unsafe fn owned_value(value: *mut ffi::sqlite3_value) -> Value<'static> {
+	match ffi::sqlite3_value_type(value) {
+		ffi::SQLITE_INTEGER => Value::Integer(ffi::sqlite3_value_int64(value)),
+		ffi::SQLITE_FLOAT => Value::Float(ffi::sqlite3_value_double(value)),
+		ffi::SQLITE_TEXT => {
+			let ptr = ffi::sqlite3_value_text(value);
+			let len = ffi::sqlite3_value_bytes(value) as usize;
+			let bytes = std::slice::from_raw_parts(ptr, len);
+			Value::Text(Mown::Owned(String::from_utf8_lossy(bytes).into_owned()))
+		},
+		ffi::SQLITE_BLOB => {
+			let len = ffi::sqlite3_value_bytes(value) as usize;
+			let ptr = ffi::sqlite3_value_blob(value) as *const u8;
+			if len == 0 {
+				Value::Blob(Mown::Owned(Vec::new()))
+			} else {
+				Value::Blob(Mown::Owned(std::slice::from_raw_parts(ptr, len).to_vec()))
+			}
+		},
+		_ => Value::Null
 	}
 }
 
-impl std::error::Error for SqliteError {
-	//
+/// Quote an SQL identifier (such as a table or schema name).
+///
+/// The identifier is wrapped in double quotes, and every double quote it contains is doubled.
+pub fn quote_identifier(name: &str) -> String {
+	let mut quoted = String::with_capacity(name.len() + 2);
+	quoted.push('"');
+	for c in name.chars() {
+		if c == '"' {
+			quoted.push('"');
+		}
+		quoted.push(c);
+	}
+	quoted.push('"');
+	quoted
 }
 
-impl From<SqliteError> for crate::Error {
-	fn from(e: SqliteError) -> crate::Error {
-		let kind = match e {
-			SqliteError::Schema => ErrorKind::SchemaChanged,
-			SqliteError::Constraint => ErrorKind::ConstraintViolation,
-			_ => ErrorKind::Failure
-		};
-
-		crate::Error::new(kind, Some(Box::new(e)))
+/// Checks if the linked SQLite library supports the JSONB format (SQLite 3.45.0 or later).
+pub fn supports_jsonb() -> bool {
+	unsafe {
+		ffi::sqlite3_libversion_number() >= 3045000
 	}
 }
 
-fn check(code: c_int) -> std::result::Result<(), SqliteError> {
-	let primary = code & 0xff;
-	let extended = code >> 8;
-
-	// if primary != ffi::SQLITE_OK {
-	// 	println!("code: {}, {} ({})", primary, extended, code);
-	// }
-
-	match primary {
-		ffi::SQLITE_OK => Ok(()),
-		ffi::SQLITE_ERROR => Err(SqliteError::Unknown),
-		ffi::SQLITE_INTERNAL => Err(SqliteError::Internal),
-		ffi::SQLITE_PERM => Err(SqliteError::Perm),
-		ffi::SQLITE_ABORT => Err(SqliteError::Abort),
-		ffi::SQLITE_BUSY => Err(SqliteError::Busy),
-		ffi::SQLITE_LOCKED => Err(SqliteError::Locked),
-		ffi::SQLITE_NOMEM => Err(SqliteError::NoMem),
-		ffi::SQLITE_READONLY => Err(SqliteError::ReadOnly),
-		ffi::SQLITE_INTERRUPT => Err(SqliteError::Interrupt),
-		ffi::SQLITE_IOERR => Err(SqliteError::IO),
-		ffi::SQLITE_CORRUPT => Err(SqliteError::Corrupt),
-		ffi::SQLITE_NOTFOUND => Err(SqliteError::NotFound),
-		ffi::SQLITE_FULL => Err(SqliteError::Full),
-		ffi::SQLITE_CANTOPEN => Err(SqliteError::CantOpen),
-		ffi::SQLITE_PROTOCOL => Err(SqliteError::Protocol),
-		ffi::SQLITE_EMPTY => Err(SqliteError::Empty),
-		ffi::SQLITE_SCHEMA => Err(SqliteError::Schema),
-		ffi::SQLITE_TOOBIG => Err(SqliteError::TooBig),
-		ffi::SQLITE_CONSTRAINT => Err(SqliteError::Constraint),
-		ffi::SQLITE_MISMATCH => Err(SqliteError::Mismatch),
-		ffi::SQLITE_MISUSE => Err(SqliteError::Misuse),
-		ffi::SQLITE_NOLFS => Err(SqliteError::NoLFS),
-		ffi::SQLITE_AUTH => Err(SqliteError::Auth),
-		ffi::SQLITE_FORMAT => Err(SqliteError::Format),
-		ffi::SQLITE_RANGE => Err(SqliteError::Range),
-		ffi::SQLITE_NOTADB => Err(SqliteError::NotADB),
-		_ => Err(SqliteError::Unknown)
+/// Byte range of the first statement of the given SQL, without surrounding whitespace.
+fn first_statement(sql: &str) -> Range<usize> {
+	match crate::parsing::split_statement_list(sql).next() {
+		Some(text) => {
+			let trimmed = text.trim_start();
+			let start = (text.as_ptr() as usize - sql.as_ptr() as usize) + (text.len() - trimmed.len());
+			start..(start + trimmed.trim_end().len())
+		},
+		None => 0..sql.len()
 	}
 }
 
-// fn str_to_cstring(s: &str) -> Result<CString> {
-// 	Ok(CString::new(s).map_err(|_| ErrorKind::InvalidString(s.to_string()).err())?)
-// }
-
 #[cfg(unix)]
 fn path_to_cstring(p: &Path) -> Result<CString> {
 	use std::os::unix::ffi::OsStrExt;
@@ -160,57 +171,169 @@ impl Connection {
 	/// Open a new connection to the given file path.
 	///
 	/// If path is `:memory:`, it will open a new in-memory connection.
+	/// Paths starting with `file:` are not interpreted as URI filenames, see [`Connection::open_uri`].
 	pub fn open<P: AsRef<Path>>(path: P) -> Result<Connection> {
+		let path = path.as_ref();
+		match path.to_str() {
+			// URI filenames are enabled on every connection to attach read-only databases,
+			// so such paths are given as escaped URIs.
+			Some(str) if str.starts_with("file:") => Self::open_v2(str_to_cstring(&attach::uri_filename(str))?),
+			_ => Self::open_v2(path_to_cstring(path)?)
+		}
+	}
+
+	/// Open a new connection to the given URI filename, such as `file:data.db?mode=ro`.
+	pub fn open_uri(uri: &str) -> Result<Connection> {
+		Self::open_v2(str_to_cstring(uri)?)
+	}
+
+	fn open_v2(filename: CString) -> Result<Connection> {
 		unsafe {
 			let mut handle = std::ptr::null_mut();
-			let c_path = path_to_cstring(path.as_ref())?;
-			check(ffi::sqlite3_open(c_path.as_ptr(), &mut handle))?;
+			let flags = ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE | ffi::SQLITE_OPEN_URI;
+			let res = ffi::sqlite3_open_v2(filename.as_ptr(), &mut handle, flags, std::ptr::null());
+			if let Err(e) = check_db(handle, res, None) {
+				// A connection handle is allocated even if the database cannot be opened.
+				ffi::sqlite3_close(handle);
+				return Err(e.into())
+			}
+
 			Ok(Connection {
 				handle: handle,
-				next_savepoint: 0
+				next_savepoint: 0,
+				attached: Vec::new(),
+				trace: None
 			})
 		}
 	}
 }
 
-impl crate::Connection for Connection {
-	type Statement = Statement;
-
-	/// Compile an SQL statement.
+impl Connection {
+	/// Compile the first SQL statement of `sql[offset..]`.
 	///
-	/// The string must consist of a single SQL statement,
-	/// with no terminating semicolon (`;`).
-	fn prepare(&mut self, sql: &str) -> Result<Option<Statement>> {
+	/// Returns the statement, if any, and the byte index following it.
+	/// The returned index is always greater than `offset`, unless `offset` is the end of `sql`.
+	fn prepare_next(&mut self, sql: &str, offset: usize) -> Result<(Option<Statement>, usize)> {
+		let rest = &sql[offset..];
 		unsafe {
 			let mut handle = std::ptr::null_mut();
-			check(ffi::sqlite3_prepare_v2(
+			let mut tail = std::ptr::null();
+			let res = ffi::sqlite3_prepare_v2(
 				self.handle,
-				&sql.as_bytes()[0] as *const u8 as *const i8,
-				sql.len() as i32,
+				rest.as_ptr() as *const c_char,
+				rest.len() as c_int,
 				&mut handle,
-				std::ptr::null_mut()
-			))?;
+				&mut tail
+			);
+
+			if res != ffi::SQLITE_OK {
+				// The tail is not set on error, so the failing statement is found by the parser.
+				let error = DatabaseError::last(self.handle, res, Some(rest));
+				return Err(error.in_statement(rest, first_statement(rest)).into())
+			}
+
+			let end = if tail.is_null() || tail as usize <= rest.as_ptr() as usize {
+				sql.len()
+			} else {
+				offset + (tail as usize - rest.as_ptr() as usize)
+			};
 
 			if handle.is_null() {
-				Ok(None)
+				Ok((None, end))
 			} else {
-				Ok(Some(Statement {
-					handle
-				}))
+				// Empty statements (`;`) may precede the statement.
+				let text = &sql[offset..end];
+				let start = offset + (text.len() - text.trim_start_matches(|c: char| c.is_whitespace() || c == ';').len());
+				Ok((Some(Statement {
+					handle,
+					span: start..(offset + text.trim_end().len())
+				}), end))
 			}
 		}
 	}
 
-	fn execute<'a, R: 'a + FromRow>(&mut self, statement: &'a Self::Statement, args: Vec<Value>) -> LocalBoxFuture<'a, Result<Option<crate::Rows<'a, R>>>> {
-		let exec = statement.execute(self, args);
+	/// Checks if `sql[offset..]` contains no statement.
+	fn is_empty_script(&mut self, sql: &str, mut offset: usize) -> bool {
+		while offset < sql.len() {
+			match self.prepare_next(sql, offset) {
+				Ok((None, end)) => offset = end,
+				_ => return false
+			}
+		}
+
+		true
+	}
+
+	/// Total number of rows inserted, updated or deleted since the connection was opened,
+	/// including rows modified by triggers and foreign key actions.
+	pub fn total_changes(&self) -> u64 {
+		unsafe {
+			sqlite3_total_changes64(self.handle) as u64
+		}
+	}
+
+	/// Row identifier of the most recent successful insertion on this connection.
+	pub fn last_insert_rowid(&self) -> i64 {
+		unsafe {
+			ffi::sqlite3_last_insert_rowid(self.handle)
+		}
+	}
+}
+
+impl crate::Connection for Connection {
+	type Statement = Statement;
+
+	/// Compile an SQL statement.
+	///
+	/// The string must consist of a single SQL statement, optionally terminated by a semicolon (`;`).
+	/// Returns a `MultipleStatements` error if it contains more than one statement.
+	fn prepare(&mut self, sql: &str) -> Result<Option<Statement>> {
+		let (statement, end) = self.prepare_next(sql, 0)?;
+		if statement.is_some() && !self.is_empty_script(sql, end) {
+			return Err(ErrorKind::MultipleStatements.err())
+		}
+
+		Ok(statement)
+	}
+
+	/// Compile a list of SQL statements.
+	///
+	/// Statements are delimited by the SQLite tokenizer.
+	fn prepare_list(&mut self, sql: &str) -> Result<Vec<Statement>> {
+		let mut statements = Vec::new();
+		let mut offset = 0;
+		while offset < sql.len() {
+			let (statement, end) = self.prepare_next(sql, offset)?;
+			statements.extend(statement);
+			offset = end
+		}
+
+		Ok(statements)
+	}
+
+	/// Prepare and execute a list of statements.
+	///
+	/// Statements are delimited by the SQLite tokenizer,
+	/// and each statement is prepared once the previous one has been executed.
+	fn execute_script<'a>(&'a mut self, sql: &'a str) -> LocalBoxFuture<'a, Result<()>> where Self::Statement: 'a {
 		async move {
-			match exec.await {
-				Ok(Some(rows)) => {
-					Ok(Some(crate::Rows::new(rows)))
-				},
-				Ok(None) => Ok(None),
-				Err(e) => Err(e)
+			let mut offset = 0;
+			while offset < sql.len() {
+				let (statement, end) = self.prepare_next(sql, offset)?;
+				if let Some(statement) = statement {
+					self.execute::<()>(&statement, vec![]).await?;
+				}
+				offset = end
 			}
+
+			Ok(())
+		}.boxed_local()
+	}
+
+	fn execute<'a, R: 'a + FromRow>(&mut self, statement: &'a Self::Statement, args: Vec<Value>) -> LocalBoxFuture<'a, Result<Execution<crate::Rows<'a, R>>>> {
+		let exec = statement.execute(self, args);
+		async move {
+			Ok(exec.await?.map_rows(crate::Rows::new))
 		}.boxed_local()
 	}
 }
@@ -234,10 +357,151 @@ impl crate::SavepointCapable for Connection {
 }
 
 pub struct Statement {
-	handle: *mut ffi::sqlite3_stmt
+	handle: *mut ffi::sqlite3_stmt,
+
+	/// Byte range of the statement in the SQL text it was prepared from.
+	span: Range<usize>
+}
+
+/// Statement with typed parameters and rows.
+pub type Query<P, R> = crate::Query<P, R, Statement>;
+
+impl crate::StatementInfo for Statement {
+	fn parameter_count(&self) -> usize {
+		self.parameter_count()
+	}
+
+	fn column_count(&self) -> usize {
+		self.column_count()
+	}
+}
+
+/// Kind of `EXPLAIN` statement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExplainMode {
+	/// `EXPLAIN` statement, listing the bytecode of a statement.
+	Explain,
+
+	/// `EXPLAIN QUERY PLAN` statement.
+	QueryPlan
 }
 
 impl Statement {
+	/// Byte range of the statement in the SQL text it was prepared from
+	/// (by `prepare`, `prepare_list` or `execute_script`).
+	///
+	/// Surrounding whitespace and empty statements are excluded,
+	/// but leading comments and the terminating semicolon are included.
+	pub fn span(&self) -> Range<usize> {
+		self.span.clone()
+	}
+
+	/// Original SQL text of the statement.
+	pub fn sql(&self) -> &str {
+		unsafe {
+			let sql = ffi::sqlite3_sql(self.handle);
+			if sql.is_null() {
+				""
+			} else {
+				std::str::from_utf8_unchecked(CStr::from_ptr(sql).to_bytes())
+			}
+		}
+	}
+
+	/// SQL text of the statement with its parameters expanded to their currently bound values.
+	///
+	/// Returns `None` if the expanded text exceeds the SQLite length limit.
+	pub fn expanded_sql(&self) -> Option<String> {
+		unsafe {
+			let sql = ffi::sqlite3_expanded_sql(self.handle);
+			if sql.is_null() {
+				None
+			} else {
+				let string = CStr::from_ptr(sql).to_string_lossy().into_owned();
+				ffi::sqlite3_free(sql as *mut c_void);
+				Some(string)
+			}
+		}
+	}
+
+	/// Number of parameters of the statement.
+	///
+	/// Numbered parameters (`?NNN`) may leave gaps in the parameters list,
+	/// so this is the index of the largest parameter.
+	pub fn parameter_count(&self) -> usize {
+		unsafe {
+			ffi::sqlite3_bind_parameter_count(self.handle) as usize
+		}
+	}
+
+	/// Name of the parameter at the given index, starting from `0`.
+	///
+	/// The name includes the initial `:`, `@`, `$` or `?` character.
+	/// Returns `None` for nameless (`?`) parameters, or if the index is out of range.
+	pub fn parameter_name(&self, index: usize) -> Option<&str> {
+		unsafe {
+			let name = ffi::sqlite3_bind_parameter_name(self.handle, index as c_int + 1);
+			if name.is_null() {
+				None
+			} else {
+				Some(std::str::from_utf8_unchecked(CStr::from_ptr(name).to_bytes()))
+			}
+		}
+	}
+
+	/// Names of the parameters of the statement, by index.
+	pub fn parameter_names(&self) -> Vec<Option<&str>> {
+		(0..self.parameter_count()).map(|i| self.parameter_name(i)).collect()
+	}
+
+	/// Number of columns returned by the statement.
+	///
+	/// This is `0` for statements that do not return rows.
+	pub fn column_count(&self) -> usize {
+		unsafe {
+			ffi::sqlite3_column_count(self.handle) as usize
+		}
+	}
+
+	/// Name of the column at the given index, starting from `0`.
+	pub fn column_name(&self, index: usize) -> Option<&str> {
+		if index < self.column_count() {
+			unsafe {
+				let name = ffi::sqlite3_column_name(self.handle, index as c_int);
+				if name.is_null() {
+					None
+				} else {
+					Some(std::str::from_utf8_unchecked(CStr::from_ptr(name).to_bytes()))
+				}
+			}
+		} else {
+			None
+		}
+	}
+
+	/// Checks if the statement makes no direct change to the database file.
+	pub fn is_readonly(&self) -> bool {
+		unsafe {
+			ffi::sqlite3_stmt_readonly(self.handle) != 0
+		}
+	}
+
+	/// Kind of `EXPLAIN` statement, or `None` if this is not an `EXPLAIN` statement.
+	pub fn explain_mode(&self) -> Option<ExplainMode> {
+		unsafe {
+			match ffi::sqlite3_stmt_isexplain(self.handle) {
+				1 => Some(ExplainMode::Explain),
+				2 => Some(ExplainMode::QueryPlan),
+				_ => None
+			}
+		}
+	}
+
+	/// Checks if this is an `EXPLAIN` or `EXPLAIN QUERY PLAN` statement.
+	pub fn is_explain(&self) -> bool {
+		self.explain_mode().is_some()
+	}
+
 	fn bind(&self, index: usize, value: Value) -> Result<()> {
 		unsafe {
 			let i = index as i32 + 1;
@@ -249,12 +513,20 @@ impl Statement {
 				Value::Null => ffi::sqlite3_bind_null(self.handle, i)
 			};
 
-			check(res)?;
+			check_db(ffi::sqlite3_db_handle(self.handle), res, None)?;
 			Ok(())
 		}
 	}
 
+	/// Reset the statement and bind the given arguments.
+	///
+	/// Parameters without argument are bound to `NULL`.
 	fn bind_all(&self, args: Vec<Value>) -> Result<()> {
+		unsafe {
+			ffi::sqlite3_reset(self.handle);
+			ffi::sqlite3_clear_bindings(self.handle);
+		}
+
 		let mut i = 0;
 		for arg in args {
 			self.bind(i, arg)?;
@@ -268,38 +540,74 @@ impl Statement {
 	///
 	/// This is a non-blocking method. A `ErrorKind::Busy` error will be raised if the database
 	/// is busy.
-	fn try_execute<R>(&self, args: Vec<Value>) -> Result<Option<Rows<R>>> {
-		self.bind_all(args);
+	fn try_execute<R>(&self) -> Result<Execution<Rows<R>>> {
 		unsafe {
+			let db = ffi::sqlite3_db_handle(self.handle);
 			let column_count = ffi::sqlite3_column_count(self.handle);
+			let total_changes = sqlite3_total_changes64(db);
 			match ffi::sqlite3_step(self.handle) {
 				ffi::SQLITE_DONE => {
 					if column_count > 0 {
-						Ok(Some(Rows::empty(self, column_count as usize)))
+						Ok(Execution::Rows(Rows::empty(self, column_count as usize)))
 					} else {
-						Ok(None)
+						// `sqlite3_changes64` is not updated by statements other than
+						// `INSERT`, `UPDATE` and `DELETE`.
+						let rows_affected = if sqlite3_total_changes64(db) == total_changes {
+							0
+						} else {
+							sqlite3_changes64(db) as u64
+						};
+
+						Ok(Execution::Done(ExecResult {
+							rows_affected,
+							last_insert_rowid: ffi::sqlite3_last_insert_rowid(db)
+						}))
 					}
 				},
 				ffi::SQLITE_ROW => {
-					Ok(Some(Rows::new(self, column_count as usize)))
+					Ok(Execution::Rows(Rows::new(self, column_count as usize)))
 				},
 				res => {
-					check(res)?;
-					Ok(None)
+					Err(DatabaseError::last(db, res, Some(self.sql())).into())
 				}
 			}
 		}
 	}
 
-	fn execute<'a, R>(&'a self, _connection: &mut Connection, args: Vec<Value>) -> impl 'a + Future<Output=Result<Option<Rows<'a, R>>>> {
+	fn execute<'a, R>(&'a self, _connection: &mut Connection, args: Vec<Value>) -> impl 'a + Future<Output=Result<Execution<Rows<'a, R>>>> {
 		let mut backoff = backoff::ExponentialBackoff::default();
-		self.bind_all(args);
+		let bound = self.bind_all(args);
 		async move {
-			async move { self.try_execute(Vec::new()) }.with_backoff(&mut backoff).await
+			bound?;
+			async move { self.try_execute() }.with_backoff(&mut backoff).await
 		}
 	}
 }
 
+#[cfg(feature = "serde")]
+impl Statement {
+	/// Arguments of the named parameters of this statement, taken from the fields of the given struct or map.
+	///
+	/// Parameters are matched by name, ignoring their `:`, `@` or `$` prefix.
+	/// Fields that do not match any parameter are ignored.
+	pub fn named_arguments<T: serde::Serialize + ?Sized>(&self, value: &T) -> Result<Vec<Value<'static>>> {
+		let mut values = crate::ser::to_named_values(value)?;
+		self.parameter_names().into_iter().map(|name| {
+			let key = name.filter(|name| !name.starts_with('?')).map(|name| &name[1..]);
+			match key.and_then(|key| values.iter().position(|(k, _)| k == key)) {
+				Some(i) => Ok(values.swap_remove(i).1),
+				None => Err(ErrorKind::InvalidArgument(name.unwrap_or("?").to_string()).err())
+			}
+		}).collect()
+	}
+}
+
+impl fmt::Debug for Statement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Statement").field("sql", &self.sql()).finish()
+	}
+}
+
 impl Drop for Statement {
 	fn drop(&mut self) {
 		unsafe {
@@ -354,7 +662,7 @@ impl<'a, R: FromRow> Stream for Rows<'a, R> {
 			if self.first_row {
 				self.first_row = false;
 				let row = Row::new(&self);
-				Poll::Ready(Some(Ok(R::from(row))))
+				Poll::Ready(Some(R::try_from_row(row)))
 			} else {
 				match ffi::sqlite3_step(self.statement.handle) {
 					ffi::SQLITE_DONE => {
@@ -362,7 +670,7 @@ impl<'a, R: FromRow> Stream for Rows<'a, R> {
 					},
 					ffi::SQLITE_ROW => {
 						let row = Row::new(&self);
-						Poll::Ready(Some(Ok(R::from(row))))
+						Poll::Ready(Some(R::try_from_row(row)))
 					},
 					ffi::SQLITE_BUSY => {
 						match self.backoff().poll(cx) {
@@ -371,8 +679,8 @@ impl<'a, R: FromRow> Stream for Rows<'a, R> {
 						}
 					},
 					res => {
-						check(res)?;
-						unreachable!()
+						let db = ffi::sqlite3_db_handle(self.statement.handle);
+						Poll::Ready(Some(Err(DatabaseError::last(db, res, Some(self.statement.sql())).into())))
 					}
 				}
 			}
@@ -402,6 +710,16 @@ impl<'a, R> Row<'a, R> {
 	}
 }
 
+impl<'a, R> crate::RowValues<'a> for Row<'a, R> {
+	fn column_count(&self) -> usize {
+		self.rows.column_count
+	}
+
+	fn column_name(&self, index: usize) -> Option<&str> {
+		self.rows.statement.column_name(index)
+	}
+}
+
 impl<'a, R> Iterator for Row<'a, R> {
 	type Item = Value<'a>;
 
@@ -421,7 +739,12 @@ impl<'a, R> Iterator for Row<'a, R> {
 					ffi::SQLITE_BLOB => {
 						let len = ffi::sqlite3_column_bytes(self.rows.statement.handle, i) as usize;
 						let ptr = ffi::sqlite3_column_blob(self.rows.statement.handle, i) as *const u8;
-						Value::Blob(Mown::Borrowed(std::slice::from_raw_parts(ptr, len)))
+						if len == 0 {
+							// The pointer is null for zero-length blobs.
+							Value::Blob(Mown::Borrowed(&[]))
+						} else {
+							Value::Blob(Mown::Borrowed(std::slice::from_raw_parts(ptr, len)))
+						}
 					},
 					_ => Value::Null
 				}
@@ -0,0 +1,198 @@
+use std::path::{
+	Path,
+	PathBuf
+};
+use libsqlite3_sys as ffi;
+
+use crate::{
+	Connection as _,
+	Result,
+	ErrorKind,
+	Value
+};
+use super::{
+	Connection,
+	quote_identifier,
+	str_to_cstring
+};
+
+/// Database file to attach to a connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttachOptions {
+	path: PathBuf,
+	read_only: bool
+}
+
+impl AttachOptions {
+	/// Attach the database file at the given path.
+	///
+	/// If path is `:memory:`, a new in-memory database is attached.
+	pub fn new<P: AsRef<Path>>(path: P) -> AttachOptions {
+		AttachOptions {
+			path: path.as_ref().to_owned(),
+			read_only: false
+		}
+	}
+
+	/// Attach a new in-memory database.
+	pub fn memory() -> AttachOptions {
+		AttachOptions::new(":memory:")
+	}
+
+	/// Open the database in read-only mode.
+	pub fn read_only(mut self, read_only: bool) -> AttachOptions {
+		self.read_only = read_only;
+		self
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	pub fn is_read_only(&self) -> bool {
+		self.read_only
+	}
+
+	/// Filename given to the `ATTACH` statement.
+	fn filename(&self) -> Result<String> {
+		let path = self.path.to_str().ok_or_else(|| ErrorKind::InvalidPath(self.path.clone()).err())?;
+		let filename = uri_filename(path);
+		if self.read_only {
+			Ok(filename + "?mode=ro")
+		} else {
+			Ok(filename)
+		}
+	}
+}
+
+/// URI filename of the given path.
+pub(super) fn uri_filename(path: &str) -> String {
+	let mut uri = "file:".to_string();
+	for c in path.chars() {
+		match c {
+			'%' => uri.push_str("%25"),
+			'?' => uri.push_str("%3f"),
+			'#' => uri.push_str("%23"),
+			c => uri.push(c)
+		}
+	}
+
+	uri
+}
+
+impl<'a> From<&'a str> for AttachOptions {
+	fn from(path: &'a str) -> AttachOptions {
+		AttachOptions::new(path)
+	}
+}
+
+impl From<String> for AttachOptions {
+	fn from(path: String) -> AttachOptions {
+		AttachOptions::new(path)
+	}
+}
+
+impl<'a> From<&'a Path> for AttachOptions {
+	fn from(path: &'a Path) -> AttachOptions {
+		AttachOptions::new(path)
+	}
+}
+
+impl From<PathBuf> for AttachOptions {
+	fn from(path: PathBuf) -> AttachOptions {
+		AttachOptions {
+			path,
+			read_only: false
+		}
+	}
+}
+
+/// Database attached to a connection with [`Connection::attach`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attachment {
+	/// Schema name of the attached database.
+	pub schema: String,
+
+	/// Options used to attach the database.
+	pub options: AttachOptions
+}
+
+/// Database opened by a connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Database {
+	/// Schema name of the database (`main`, `temp` or the name given when attached).
+	pub schema: String,
+
+	/// Path of the database file, or `None` for in-memory and temporary databases.
+	pub path: Option<PathBuf>,
+
+	/// Whether the database is read-only.
+	pub read_only: bool
+}
+
+impl Connection {
+	/// Attach a database to this connection under the given schema name.
+	///
+	/// Read-only databases are opened using an URI filename.
+	pub async fn attach<O: Into<AttachOptions>>(&mut self, options: O, schema: &str) -> Result<()> {
+		let options = options.into();
+		let sql = format!("ATTACH DATABASE ? AS {}", quote_identifier(schema));
+		let stmt = self.prepare(&sql)?.unwrap();
+		self.execute::<()>(&stmt, vec![Value::from(options.filename()?)]).await?;
+
+		self.attached.push(Attachment {
+			schema: schema.to_string(),
+			options
+		});
+
+		Ok(())
+	}
+
+	/// Attach every given database to this connection.
+	///
+	/// This can be used to replicate the attachments of a connection onto another,
+	/// using [`Connection::attachments`].
+	pub async fn attach_all(&mut self, attachments: &[Attachment]) -> Result<()> {
+		for attachment in attachments {
+			self.attach(attachment.options.clone(), &attachment.schema).await?;
+		}
+
+		Ok(())
+	}
+
+	/// Detach the database with the given schema name.
+	pub async fn detach(&mut self, schema: &str) -> Result<()> {
+		let sql = format!("DETACH DATABASE {}", quote_identifier(schema));
+		let stmt = self.prepare(&sql)?.unwrap();
+		self.execute::<()>(&stmt, vec![]).await?;
+
+		self.attached.retain(|a| !a.schema.eq_ignore_ascii_case(schema));
+		Ok(())
+	}
+
+	/// Databases attached through [`Connection::attach`], in attachment order.
+	pub fn attachments(&self) -> &[Attachment] {
+		&self.attached
+	}
+
+	/// List every database opened by this connection, including `main` and `temp`.
+	pub async fn databases(&mut self) -> Result<Vec<Database>> {
+		let list: Vec<(i64, String, String)> = self.fetch_all("PRAGMA database_list", vec![]).await?;
+
+		let mut databases = Vec::with_capacity(list.len());
+		for (_, schema, file) in list {
+			let c_schema = str_to_cstring(&schema)?;
+			let read_only = unsafe {
+				ffi::sqlite3_db_readonly(self.handle, c_schema.as_ptr()) == 1
+			};
+
+			databases.push(Database {
+				schema,
+				path: if file.is_empty() { None } else { Some(PathBuf::from(file)) },
+				read_only
+			})
+		}
+
+		Ok(databases)
+	}
+}
@@ -0,0 +1,318 @@
+use std::fmt;
+use std::convert::TryFrom;
+use std::ops::Range;
+use std::ffi::CStr;
+use std::os::raw::{
+	c_char,
+	c_int
+};
+use libsqlite3_sys as ffi;
+
+use crate::ErrorKind;
+
+extern "C" {
+	// Not part of the bindings of the minimum SQLite version supported by `libsqlite3-sys`.
+	fn sqlite3_error_offset(db: *mut ffi::sqlite3) -> c_int;
+}
+
+/// Primary result code of an SQLite error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SqliteError {
+	Unknown,
+	Internal,
+	Perm,
+	Abort,
+	Busy,
+	Locked,
+	NoMem,
+	ReadOnly,
+	Interrupt,
+	IO,
+	Corrupt,
+	NotFound,
+	Full,
+	CantOpen,
+	Protocol,
+	Empty,
+	Schema,
+	TooBig,
+	Constraint,
+	Mismatch,
+	Misuse,
+	NoLFS,
+	Auth,
+	Format,
+	Range,
+	NotADB
+}
+
+impl SqliteError {
+	/// Error with the given (primary or extended) result code.
+	pub fn from_code(code: c_int) -> SqliteError {
+		match code & 0xff {
+			ffi::SQLITE_INTERNAL => SqliteError::Internal,
+			ffi::SQLITE_PERM => SqliteError::Perm,
+			ffi::SQLITE_ABORT => SqliteError::Abort,
+			ffi::SQLITE_BUSY => SqliteError::Busy,
+			ffi::SQLITE_LOCKED => SqliteError::Locked,
+			ffi::SQLITE_NOMEM => SqliteError::NoMem,
+			ffi::SQLITE_READONLY => SqliteError::ReadOnly,
+			ffi::SQLITE_INTERRUPT => SqliteError::Interrupt,
+			ffi::SQLITE_IOERR => SqliteError::IO,
+			ffi::SQLITE_CORRUPT => SqliteError::Corrupt,
+			ffi::SQLITE_NOTFOUND => SqliteError::NotFound,
+			ffi::SQLITE_FULL => SqliteError::Full,
+			ffi::SQLITE_CANTOPEN => SqliteError::CantOpen,
+			ffi::SQLITE_PROTOCOL => SqliteError::Protocol,
+			ffi::SQLITE_EMPTY => SqliteError::Empty,
+			ffi::SQLITE_SCHEMA => SqliteError::Schema,
+			ffi::SQLITE_TOOBIG => SqliteError::TooBig,
+			ffi::SQLITE_CONSTRAINT => SqliteError::Constraint,
+			ffi::SQLITE_MISMATCH => SqliteError::Mismatch,
+			ffi::SQLITE_MISUSE => SqliteError::Misuse,
+			ffi::SQLITE_NOLFS => SqliteError::NoLFS,
+			ffi::SQLITE_AUTH => SqliteError::Auth,
+			ffi::SQLITE_FORMAT => SqliteError::Format,
+			ffi::SQLITE_RANGE => SqliteError::Range,
+			ffi::SQLITE_NOTADB => SqliteError::NotADB,
+			_ => SqliteError::Unknown
+		}
+	}
+
+	/// Primary result code.
+	pub fn code(&self) -> c_int {
+		match self {
+			SqliteError::Unknown => ffi::SQLITE_ERROR,
+			SqliteError::Internal => ffi::SQLITE_INTERNAL,
+			SqliteError::Perm => ffi::SQLITE_PERM,
+			SqliteError::Abort => ffi::SQLITE_ABORT,
+			SqliteError::Busy => ffi::SQLITE_BUSY,
+			SqliteError::Locked => ffi::SQLITE_LOCKED,
+			SqliteError::NoMem => ffi::SQLITE_NOMEM,
+			SqliteError::ReadOnly => ffi::SQLITE_READONLY,
+			SqliteError::Interrupt => ffi::SQLITE_INTERRUPT,
+			SqliteError::IO => ffi::SQLITE_IOERR,
+			SqliteError::Corrupt => ffi::SQLITE_CORRUPT,
+			SqliteError::NotFound => ffi::SQLITE_NOTFOUND,
+			SqliteError::Full => ffi::SQLITE_FULL,
+			SqliteError::CantOpen => ffi::SQLITE_CANTOPEN,
+			SqliteError::Protocol => ffi::SQLITE_PROTOCOL,
+			SqliteError::Empty => ffi::SQLITE_EMPTY,
+			SqliteError::Schema => ffi::SQLITE_SCHEMA,
+			SqliteError::TooBig => ffi::SQLITE_TOOBIG,
+			SqliteError::Constraint => ffi::SQLITE_CONSTRAINT,
+			SqliteError::Mismatch => ffi::SQLITE_MISMATCH,
+			SqliteError::Misuse => ffi::SQLITE_MISUSE,
+			SqliteError::NoLFS => ffi::SQLITE_NOLFS,
+			SqliteError::Auth => ffi::SQLITE_AUTH,
+			SqliteError::Format => ffi::SQLITE_FORMAT,
+			SqliteError::Range => ffi::SQLITE_RANGE,
+			SqliteError::NotADB => ffi::SQLITE_NOTADB
+		}
+	}
+
+	/// Kind of error, independent of the database backend.
+	pub fn kind(&self) -> ErrorKind {
+		match self {
+			SqliteError::Unknown => ErrorKind::Failure,
+			SqliteError::Internal => ErrorKind::Internal,
+			SqliteError::Perm => ErrorKind::PermissionDenied,
+			SqliteError::Abort => ErrorKind::Aborted,
+			SqliteError::Busy => ErrorKind::Busy,
+			SqliteError::Locked => ErrorKind::Locked,
+			SqliteError::NoMem => ErrorKind::OutOfMemory,
+			SqliteError::ReadOnly => ErrorKind::ReadOnly,
+			SqliteError::Interrupt => ErrorKind::Interrupted,
+			SqliteError::IO => ErrorKind::Io,
+			SqliteError::Corrupt => ErrorKind::Corrupt,
+			SqliteError::NotFound => ErrorKind::Failure,
+			SqliteError::Full => ErrorKind::DiskFull,
+			SqliteError::CantOpen => ErrorKind::CantOpen,
+			SqliteError::Protocol => ErrorKind::Failure,
+			SqliteError::Empty => ErrorKind::Failure,
+			SqliteError::Schema => ErrorKind::SchemaChanged,
+			SqliteError::TooBig => ErrorKind::TooBig,
+			SqliteError::Constraint => ErrorKind::ConstraintViolation,
+			SqliteError::Mismatch => ErrorKind::TypeMismatch,
+			SqliteError::Misuse => ErrorKind::Misuse,
+			SqliteError::NoLFS => ErrorKind::Io,
+			SqliteError::Auth => ErrorKind::Unauthorized,
+			SqliteError::Format => ErrorKind::Failure,
+			SqliteError::Range => ErrorKind::OutOfRange,
+			SqliteError::NotADB => ErrorKind::NotADatabase
+		}
+	}
+}
+
+impl fmt::Display for SqliteError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:?}", self)
+	}
+}
+
+impl std::error::Error for SqliteError {
+	//
+}
+
+impl From<SqliteError> for crate::Error {
+	fn from(e: SqliteError) -> crate::Error {
+		DatabaseError::from(e).into()
+	}
+}
+
+/// Kind of a violated constraint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Constraint {
+	Unique,
+	ForeignKey,
+	NotNull,
+	Check,
+	PrimaryKey
+}
+
+/// Error reported by SQLite.
+#[derive(Clone, Debug)]
+pub struct DatabaseError {
+	extended_code: c_int,
+	message: String,
+	sql: Option<String>,
+	offset: Option<usize>
+}
+
+impl DatabaseError {
+	/// Error with the given result code, described by `sqlite3_errstr`.
+	pub(super) fn from_code(code: c_int) -> DatabaseError {
+		DatabaseError {
+			extended_code: code,
+			message: unsafe { message(ffi::sqlite3_errstr(code)) },
+			sql: None,
+			offset: None
+		}
+	}
+
+	/// Last error that occured on the given connection.
+	///
+	/// If the connection does not hold an error matching the given result code
+	/// (as for errors returned by `sqlite3_open_v2` when out of memory),
+	/// the error is described by `sqlite3_errstr`.
+	pub(super) unsafe fn last(db: *mut ffi::sqlite3, code: c_int, sql: Option<&str>) -> DatabaseError {
+		let extended_code = if db.is_null() { code } else { ffi::sqlite3_extended_errcode(db) };
+		if extended_code & 0xff != code & 0xff {
+			return DatabaseError::from_code(code)
+		}
+
+		let offset = match sql {
+			Some(_) => usize::try_from(sqlite3_error_offset(db)).ok(),
+			None => None
+		};
+
+		DatabaseError {
+			extended_code,
+			message: message(ffi::sqlite3_errmsg(db)),
+			sql: sql.map(str::to_string),
+			offset
+		}
+	}
+
+	/// Restrict the failing SQL to the statement at `sql[range]`.
+	///
+	/// The error offset, relative to `sql`, is made relative to the statement.
+	pub(super) fn in_statement(mut self, sql: &str, range: Range<usize>) -> DatabaseError {
+		self.offset = self.offset.and_then(|offset| offset.checked_sub(range.start)).filter(|offset| *offset <= range.len());
+		self.sql = Some(sql[range].to_string());
+		self
+	}
+
+	/// Primary result code.
+	pub fn code(&self) -> SqliteError {
+		SqliteError::from_code(self.extended_code)
+	}
+
+	/// Extended result code.
+	pub fn extended_code(&self) -> i32 {
+		self.extended_code
+	}
+
+	/// English-language description of the error.
+	pub fn message(&self) -> &str {
+		&self.message
+	}
+
+	/// SQL of the statement that failed, if any.
+	pub fn sql(&self) -> Option<&str> {
+		self.sql.as_deref()
+	}
+
+	/// Byte offset in the SQL of the token the error refers to, if any.
+	pub fn offset(&self) -> Option<usize> {
+		self.offset
+	}
+
+	/// Kind of the violated constraint, for constraint violations.
+	pub fn constraint(&self) -> Option<Constraint> {
+		match self.extended_code {
+			ffi::SQLITE_CONSTRAINT_UNIQUE => Some(Constraint::Unique),
+			ffi::SQLITE_CONSTRAINT_FOREIGNKEY => Some(Constraint::ForeignKey),
+			ffi::SQLITE_CONSTRAINT_NOTNULL => Some(Constraint::NotNull),
+			ffi::SQLITE_CONSTRAINT_CHECK => Some(Constraint::Check),
+			ffi::SQLITE_CONSTRAINT_PRIMARYKEY => Some(Constraint::PrimaryKey),
+			_ => None
+		}
+	}
+}
+
+unsafe fn message(ptr: *const c_char) -> String {
+	if ptr.is_null() {
+		String::new()
+	} else {
+		CStr::from_ptr(ptr).to_string_lossy().into_owned()
+	}
+}
+
+impl fmt::Display for DatabaseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.message)?;
+		if let Some(sql) = &self.sql {
+			write!(f, " in `{}`", sql)?;
+			if let Some(offset) = self.offset {
+				write!(f, " at offset {}", offset)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl std::error::Error for DatabaseError {
+	//
+}
+
+impl From<SqliteError> for DatabaseError {
+	fn from(e: SqliteError) -> DatabaseError {
+		DatabaseError::from_code(e.code())
+	}
+}
+
+impl From<DatabaseError> for crate::Error {
+	fn from(e: DatabaseError) -> crate::Error {
+		crate::Error::new(e.code().kind(), Some(Box::new(e)))
+	}
+}
+
+/// Check a result code that is not related to a connection.
+pub(super) fn check(code: c_int) -> std::result::Result<(), DatabaseError> {
+	match code {
+		ffi::SQLITE_OK => Ok(()),
+		code => Err(DatabaseError::from_code(code))
+	}
+}
+
+/// Check a result code returned by a function operating on the given connection.
+///
+/// The SQL of the statement that caused the error can be given.
+pub(super) unsafe fn check_db(db: *mut ffi::sqlite3, code: c_int, sql: Option<&str>) -> std::result::Result<(), DatabaseError> {
+	match code {
+		ffi::SQLITE_OK => Ok(()),
+		code => Err(DatabaseError::last(db, code, sql))
+	}
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+
+use crate::{
+	Connection as _,
+	Result,
+	FromRow,
+	RowValues,
+	Value
+};
+use super::{
+	Connection,
+	Statement
+};
+
+/// Query plan of a statement, as given by `EXPLAIN QUERY PLAN`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryPlan {
+	/// Top-level steps of the plan.
+	pub nodes: Vec<PlanNode>
+}
+
+/// Step of a query plan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlanNode {
+	pub id: i64,
+
+	/// Description of the step (for instance `SCAN foo` or `SEARCH foo USING INDEX foo_bar (bar=?)`).
+	pub detail: String,
+
+	/// Sub-steps.
+	pub children: Vec<PlanNode>
+}
+
+impl QueryPlan {
+	/// Iterate over every step of the plan, parents first.
+	pub fn iter(&self) -> impl Iterator<Item = &PlanNode> {
+		let mut stack: Vec<&PlanNode> = self.nodes.iter().rev().collect();
+		std::iter::from_fn(move || {
+			let node = stack.pop()?;
+			stack.extend(node.children.iter().rev());
+			Some(node)
+		})
+	}
+
+	/// Checks if a table or index is scanned entirely.
+	pub fn uses_full_scan(&self) -> bool {
+		self.iter().any(PlanNode::is_full_scan)
+	}
+
+	/// Checks if the index with the given name is used.
+	pub fn uses_index(&self, name: &str) -> bool {
+		self.iter().any(|node| node.index() == Some(name))
+	}
+
+	/// Checks if a temporary b-tree is built, to sort or group rows for instance.
+	pub fn uses_temp_b_tree(&self) -> bool {
+		self.iter().any(|node| node.detail.contains("TEMP B-TREE"))
+	}
+}
+
+impl PlanNode {
+	/// Checks if this step scans a table or index entirely.
+	///
+	/// Scanning a covering index still reads every index entry,
+	/// whereas index lookups are reported as `SEARCH` steps.
+	pub fn is_full_scan(&self) -> bool {
+		self.detail.starts_with("SCAN ") && self.detail != "SCAN CONSTANT ROW"
+	}
+
+	/// Name of the index used by this step, if any.
+	///
+	/// Automatic indexes are not named.
+	pub fn index(&self) -> Option<&str> {
+		let start = self.detail.find("USING INDEX ").map(|i| i + 12)
+			.or_else(|| self.detail.find("USING COVERING INDEX ").map(|i| i + 21))?;
+		let name = &self.detail[start..];
+		Some(name.split(' ').next().unwrap_or(name))
+	}
+
+	fn fmt_tree(&self, f: &mut fmt::Formatter, prefix: &str, last: bool) -> fmt::Result {
+		writeln!(f, "{}{}{}", prefix, if last { "`--" } else { "|--" }, self.detail)?;
+		let prefix = format!("{}{}", prefix, if last { "   " } else { "|  " });
+		for (i, child) in self.children.iter().enumerate() {
+			child.fmt_tree(f, &prefix, i + 1 == self.children.len())?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Formats the plan as a tree, the same way the `sqlite3` command-line tool does.
+impl fmt::Display for QueryPlan {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "QUERY PLAN")?;
+		for (i, node) in self.nodes.iter().enumerate() {
+			node.fmt_tree(f, "", i + 1 == self.nodes.len())?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Bytecode instruction, as listed by `EXPLAIN`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instruction {
+	pub addr: i64,
+	pub opcode: String,
+	pub p1: i64,
+	pub p2: i64,
+	pub p3: i64,
+	pub p4: Option<String>,
+	pub p5: i64,
+
+	/// Only available if SQLite was compiled with `SQLITE_ENABLE_EXPLAIN_COMMENTS`.
+	pub comment: Option<String>
+}
+
+fn value_to_string(value: Value) -> Option<String> {
+	match value {
+		Value::Integer(i) => Some(i.to_string()),
+		Value::Float(f) => Some(f.to_string()),
+		Value::Text(text) => Some(text.to_string()),
+		Value::Blob(blob) => Some(String::from_utf8_lossy(&blob).into_owned()),
+		Value::Null => None
+	}
+}
+
+fn value_to_integer(value: Option<Value>) -> i64 {
+	match value {
+		Some(Value::Integer(i)) => i,
+		_ => 0
+	}
+}
+
+impl FromRow for Instruction {
+	fn from<'a, R: RowValues<'a>>(mut row: R) -> Instruction {
+		Instruction {
+			addr: value_to_integer(row.next()),
+			opcode: row.next().and_then(value_to_string).unwrap_or_default(),
+			p1: value_to_integer(row.next()),
+			p2: value_to_integer(row.next()),
+			p3: value_to_integer(row.next()),
+			p4: row.next().and_then(value_to_string),
+			p5: value_to_integer(row.next()),
+			comment: row.next().and_then(value_to_string).filter(|c| !c.is_empty())
+		}
+	}
+}
+
+/// Query plans and bytecode listing.
+///
+/// Parameters of the explained statement do not need to be bound.
+impl Connection {
+	/// Explain how the given SQL statement would be evaluated.
+	pub async fn explain_query_plan(&mut self, sql: &str) -> Result<QueryPlan> {
+		let rows: Vec<(i64, i64, i64, String)> = self.fetch_all(&format!("EXPLAIN QUERY PLAN {}", sql), vec![]).await?;
+		Ok(QueryPlan {
+			nodes: plan_nodes(&rows, 0)
+		})
+	}
+
+	/// Explain how the given prepared statement would be evaluated.
+	pub async fn explain_statement_plan(&mut self, statement: &Statement) -> Result<QueryPlan> {
+		self.explain_query_plan(statement.sql()).await
+	}
+
+	/// List the bytecode instructions of the given SQL statement.
+	pub async fn explain(&mut self, sql: &str) -> Result<Vec<Instruction>> {
+		self.fetch_all(&format!("EXPLAIN {}", sql), vec![]).await
+	}
+}
+
+/// Build the plan nodes with the given parent.
+fn plan_nodes(rows: &[(i64, i64, i64, String)], parent: i64) -> Vec<PlanNode> {
+	rows.iter().filter(|(_, p, _, _)| *p == parent).map(|(id, _, _, detail)| PlanNode {
+		id: *id,
+		detail: detail.clone(),
+		children: plan_nodes(rows, *id)
+	}).collect()
+}
@@ -0,0 +1,245 @@
+use crate::{
+	Connection as _,
+	Result
+};
+use super::{
+	Connection,
+	quote_identifier
+};
+
+/// Table of a database schema.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Table {
+	pub name: String,
+
+	/// SQL text of the `CREATE TABLE` statement.
+	pub sql: Option<String>,
+
+	pub columns: Vec<Column>,
+	pub indexes: Vec<Index>,
+	pub foreign_keys: Vec<ForeignKey>
+}
+
+/// Column of a table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Column {
+	pub name: String,
+
+	/// Declared type of the column, if any.
+	pub declared_type: Option<String>,
+
+	pub not_null: bool,
+
+	/// SQL text of the default value expression, if any.
+	pub default: Option<String>,
+
+	/// Position of the column in the table primary key, starting from 1,
+	/// or `0` if the column is not part of the primary key.
+	pub primary_key: usize
+}
+
+/// How an index was created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IndexOrigin {
+	/// Created by a `CREATE INDEX` statement.
+	CreateIndex,
+
+	/// Created by a `UNIQUE` constraint.
+	Unique,
+
+	/// Created by a `PRIMARY KEY` constraint.
+	PrimaryKey
+}
+
+/// Index of a table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Index {
+	pub name: String,
+	pub unique: bool,
+	pub origin: IndexOrigin,
+
+	/// Whether this is a partial index (with a `WHERE` clause).
+	pub partial: bool,
+
+	/// Indexed columns, in index order.
+	///
+	/// Expressions are represented by `None`.
+	pub columns: Vec<Option<String>>
+}
+
+/// Foreign key constraint of a table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignKey {
+	/// Referenced table.
+	pub table: String,
+
+	/// Referencing columns.
+	pub from: Vec<String>,
+
+	/// Referenced columns.
+	///
+	/// A column is `None` if it refers to the primary key of the referenced table implicitly.
+	pub to: Vec<Option<String>>,
+
+	/// `ON UPDATE` action (`NO ACTION`, `CASCADE`, `SET NULL`, etc.).
+	pub on_update: String,
+
+	/// `ON DELETE` action (`NO ACTION`, `CASCADE`, `SET NULL`, etc.).
+	pub on_delete: String
+}
+
+/// View of a database schema.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct View {
+	pub name: String,
+
+	/// SQL text of the `CREATE VIEW` statement.
+	pub sql: String
+}
+
+/// Trigger of a database schema.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trigger {
+	pub name: String,
+
+	/// Table or view the trigger is attached to.
+	pub table: String,
+
+	/// SQL text of the `CREATE TRIGGER` statement.
+	pub sql: String
+}
+
+/// Schema introspection.
+///
+/// Every function takes the schema name of the database to inspect,
+/// which is `main` for the main database, `temp` for the temporary database,
+/// or the name given to an attached database.
+impl Connection {
+	/// Name, associated table name and SQL text of the schema objects of the given type, in creation order.
+	async fn schema_objects(&mut self, schema: &str, ty: &str) -> Result<Vec<(String, String, Option<String>)>> {
+		let sql = format!("SELECT name, tbl_name, sql FROM {}.sqlite_schema WHERE type = ? AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\' ORDER BY rowid", quote_identifier(schema));
+		self.fetch_all(&sql, vec![ty.into()]).await
+	}
+
+	/// List the tables of the given schema.
+	///
+	/// Internal tables (whose name starts with `sqlite_`) are ignored.
+	pub async fn tables(&mut self, schema: &str) -> Result<Vec<Table>> {
+		let mut tables = Vec::new();
+		for (name, _, sql) in self.schema_objects(schema, "table").await? {
+			tables.push(self.describe_table(schema, name, sql).await?)
+		}
+
+		Ok(tables)
+	}
+
+	/// Find the table with the given name.
+	pub async fn table(&mut self, schema: &str, name: &str) -> Result<Option<Table>> {
+		let sql = format!("SELECT name, sql FROM {}.sqlite_schema WHERE type = 'table' AND name = ?", quote_identifier(schema));
+		let found: Vec<(String, Option<String>)> = self.fetch_all(&sql, vec![name.into()]).await?;
+		match found.into_iter().next() {
+			Some((name, sql)) => Ok(Some(self.describe_table(schema, name, sql).await?)),
+			None => Ok(None)
+		}
+	}
+
+	async fn describe_table(&mut self, schema: &str, name: String, sql: Option<String>) -> Result<Table> {
+		Ok(Table {
+			columns: self.table_columns(schema, &name).await?,
+			indexes: self.table_indexes(schema, &name).await?,
+			foreign_keys: self.table_foreign_keys(schema, &name).await?,
+			name,
+			sql
+		})
+	}
+
+	/// List the columns of the given table or view.
+	pub async fn table_columns(&mut self, schema: &str, table: &str) -> Result<Vec<Column>> {
+		let rows: Vec<(i64, String, String, i64, Option<String>, i64)> = self.fetch_all(
+			"SELECT cid, name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?, ?) ORDER BY cid",
+			vec![table.into(), schema.into()]
+		).await?;
+
+		Ok(rows.into_iter().map(|(_, name, ty, not_null, default, pk)| Column {
+			name,
+			declared_type: if ty.is_empty() { None } else { Some(ty) },
+			not_null: not_null != 0,
+			default,
+			primary_key: pk as usize
+		}).collect())
+	}
+
+	/// List the indexes of the given table.
+	pub async fn table_indexes(&mut self, schema: &str, table: &str) -> Result<Vec<Index>> {
+		let list: Vec<(String, i64, String, i64)> = self.fetch_all(
+			"SELECT name, \"unique\", origin, partial FROM pragma_index_list(?, ?) ORDER BY seq",
+			vec![table.into(), schema.into()]
+		).await?;
+
+		let mut indexes = Vec::with_capacity(list.len());
+		for (name, unique, origin, partial) in list {
+			let columns: Vec<Option<String>> = self.fetch_all(
+				"SELECT name FROM pragma_index_info(?, ?) ORDER BY seqno",
+				vec![name.as_str().into(), schema.into()]
+			).await?;
+
+			indexes.push(Index {
+				name,
+				unique: unique != 0,
+				origin: match origin.as_str() {
+					"u" => IndexOrigin::Unique,
+					"pk" => IndexOrigin::PrimaryKey,
+					_ => IndexOrigin::CreateIndex
+				},
+				partial: partial != 0,
+				columns
+			})
+		}
+
+		Ok(indexes)
+	}
+
+	/// List the foreign key constraints of the given table.
+	pub async fn table_foreign_keys(&mut self, schema: &str, table: &str) -> Result<Vec<ForeignKey>> {
+		let rows: Vec<(i64, String, String, Option<String>, String, String)> = self.fetch_all(
+			"SELECT id, \"table\", \"from\", \"to\", on_update, on_delete FROM pragma_foreign_key_list(?, ?) ORDER BY id, seq",
+			vec![table.into(), schema.into()]
+		).await?;
+
+		let mut foreign_keys: Vec<(i64, ForeignKey)> = Vec::new();
+		for (id, table, from, to, on_update, on_delete) in rows {
+			match foreign_keys.last_mut() {
+				Some((last_id, fk)) if *last_id == id => {
+					fk.from.push(from);
+					fk.to.push(to);
+				},
+				_ => foreign_keys.push((id, ForeignKey {
+					table,
+					from: vec![from],
+					to: vec![to],
+					on_update,
+					on_delete
+				}))
+			}
+		}
+
+		Ok(foreign_keys.into_iter().map(|(_, fk)| fk).collect())
+	}
+
+	/// List the views of the given schema.
+	pub async fn views(&mut self, schema: &str) -> Result<Vec<View>> {
+		Ok(self.schema_objects(schema, "view").await?.into_iter().map(|(name, _, sql)| View {
+			name,
+			sql: sql.unwrap_or_default()
+		}).collect())
+	}
+
+	/// List the triggers of the given schema.
+	pub async fn triggers(&mut self, schema: &str) -> Result<Vec<Trigger>> {
+		Ok(self.schema_objects(schema, "trigger").await?.into_iter().map(|(name, table, sql)| Trigger {
+			name,
+			table,
+			sql: sql.unwrap_or_default()
+		}).collect())
+	}
+}
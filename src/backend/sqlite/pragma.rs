@@ -0,0 +1,231 @@
+use std::fmt;
+use std::convert::TryFrom;
+use std::time::Duration;
+use futures::stream::StreamExt;
+
+use crate::{
+	Connection as _,
+	Result,
+	ErrorKind,
+	FromValue
+};
+use super::Connection;
+
+/// Journal mode of a database.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JournalMode {
+	Delete,
+	Truncate,
+	Persist,
+	Memory,
+	Wal,
+	Off
+}
+
+impl JournalMode {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			JournalMode::Delete => "DELETE",
+			JournalMode::Truncate => "TRUNCATE",
+			JournalMode::Persist => "PERSIST",
+			JournalMode::Memory => "MEMORY",
+			JournalMode::Wal => "WAL",
+			JournalMode::Off => "OFF"
+		}
+	}
+
+	fn parse(s: &str) -> Result<JournalMode> {
+		match s.to_ascii_uppercase().as_str() {
+			"DELETE" => Ok(JournalMode::Delete),
+			"TRUNCATE" => Ok(JournalMode::Truncate),
+			"PERSIST" => Ok(JournalMode::Persist),
+			"MEMORY" => Ok(JournalMode::Memory),
+			"WAL" => Ok(JournalMode::Wal),
+			"OFF" => Ok(JournalMode::Off),
+			_ => Err(ErrorKind::InvalidString(s.to_string()).err())
+		}
+	}
+}
+
+impl fmt::Display for JournalMode {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.as_str().fmt(f)
+	}
+}
+
+/// Synchronization level with the file system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Synchronous {
+	Off = 0,
+	Normal = 1,
+	Full = 2,
+	Extra = 3
+}
+
+/// Storage of temporary tables and indices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TempStore {
+	/// Use the compile-time default.
+	Default = 0,
+	File = 1,
+	Memory = 2
+}
+
+/// Automatic vacuum mode of a database.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AutoVacuum {
+	None = 0,
+	Full = 1,
+	Incremental = 2
+}
+
+macro_rules! integer_enum {
+	( $ty:ident { $( $i:literal => $variant:ident ),* } ) => {
+		impl TryFrom<i64> for $ty {
+			type Error = crate::Error;
+
+			fn try_from(i: i64) -> Result<$ty> {
+				match i {
+					$( $i => Ok($ty::$variant), )*
+					_ => Err(ErrorKind::InvalidArgument(i.to_string()).err())
+				}
+			}
+		}
+	};
+}
+
+integer_enum!(Synchronous { 0 => Off, 1 => Normal, 2 => Full, 3 => Extra });
+integer_enum!(TempStore { 0 => Default, 1 => File, 2 => Memory });
+integer_enum!(AutoVacuum { 0 => None, 1 => Full, 2 => Incremental });
+
+/// Typed access to the most common pragmas.
+///
+/// Every pragma applies to the `main` database.
+impl Connection {
+	/// Evaluate a pragma and return the first column of its first row, if any.
+	async fn optional_pragma<T: FromValue>(&mut self, pragma: &str) -> Result<Option<T>> {
+		let stmt = self.prepare(&format!("PRAGMA {}", pragma))?.unwrap();
+		let mut rows = self.execute::<T>(&stmt, vec![]).await?.rows().ok_or_else(|| ErrorKind::InvalidQuery.err())?;
+		rows.next().await.transpose()
+	}
+
+	/// Evaluate a pragma and return the first column of its first row.
+	async fn pragma<T: FromValue>(&mut self, pragma: &str) -> Result<T> {
+		self.optional_pragma(pragma).await?.ok_or_else(|| ErrorKind::InvalidQuery.err())
+	}
+
+	/// Evaluate a pragma, ignoring its result.
+	async fn set_pragma(&mut self, pragma: &str, value: &str) -> Result<()> {
+		let stmt = self.prepare(&format!("PRAGMA {} = {}", pragma, value))?.unwrap();
+		self.execute::<()>(&stmt, vec![]).await?;
+		Ok(())
+	}
+
+	pub async fn journal_mode(&mut self) -> Result<JournalMode> {
+		JournalMode::parse(&self.pragma::<String>("journal_mode").await?)
+	}
+
+	/// Change the journal mode.
+	///
+	/// Returns the new journal mode, which may differ from the requested one
+	/// if it is not supported by the database
+	/// (for instance, in-memory databases only support `Memory` and `Off`).
+	pub async fn set_journal_mode(&mut self, mode: JournalMode) -> Result<JournalMode> {
+		JournalMode::parse(&self.pragma::<String>(&format!("journal_mode = {}", mode)).await?)
+	}
+
+	pub async fn synchronous(&mut self) -> Result<Synchronous> {
+		Synchronous::try_from(self.pragma::<i64>("synchronous").await?)
+	}
+
+	pub async fn set_synchronous(&mut self, synchronous: Synchronous) -> Result<()> {
+		self.set_pragma("synchronous", &(synchronous as i64).to_string()).await
+	}
+
+	pub async fn foreign_keys(&mut self) -> Result<bool> {
+		Ok(self.pragma::<i64>("foreign_keys").await? != 0)
+	}
+
+	/// Enable or disable the enforcement of foreign key constraints.
+	///
+	/// This has no effect inside a transaction.
+	pub async fn set_foreign_keys(&mut self, enabled: bool) -> Result<()> {
+		self.set_pragma("foreign_keys", if enabled { "ON" } else { "OFF" }).await
+	}
+
+	/// Suggested maximum number of pages held in memory.
+	///
+	/// A negative value `-n` means that the cache is limited to `n` KiB.
+	pub async fn cache_size(&mut self) -> Result<i64> {
+		self.pragma("cache_size").await
+	}
+
+	pub async fn set_cache_size(&mut self, size: i64) -> Result<()> {
+		self.set_pragma("cache_size", &size.to_string()).await
+	}
+
+	/// Maximum number of bytes used for memory-mapped I/O.
+	///
+	/// In-memory databases do not use memory-mapped I/O and always return `0`.
+	pub async fn mmap_size(&mut self) -> Result<u64> {
+		Ok(self.optional_pragma("mmap_size").await?.unwrap_or(0))
+	}
+
+	/// Set the maximum number of bytes used for memory-mapped I/O.
+	///
+	/// The size must fit in a signed 64-bit integer.
+	/// It is silently capped by SQLite to its compile-time maximum.
+	pub async fn set_mmap_size(&mut self, size: u64) -> Result<()> {
+		let size = i64::try_from(size).map_err(|_| ErrorKind::InvalidArgument(size.to_string()).err())?;
+		self.set_pragma("mmap_size", &size.to_string()).await
+	}
+
+	pub async fn user_version(&mut self) -> Result<i32> {
+		self.pragma("user_version").await
+	}
+
+	pub async fn set_user_version(&mut self, version: i32) -> Result<()> {
+		self.set_pragma("user_version", &version.to_string()).await
+	}
+
+	pub async fn application_id(&mut self) -> Result<i32> {
+		self.pragma("application_id").await
+	}
+
+	pub async fn set_application_id(&mut self, id: i32) -> Result<()> {
+		self.set_pragma("application_id", &id.to_string()).await
+	}
+
+	pub async fn temp_store(&mut self) -> Result<TempStore> {
+		TempStore::try_from(self.pragma::<i64>("temp_store").await?)
+	}
+
+	pub async fn set_temp_store(&mut self, store: TempStore) -> Result<()> {
+		self.set_pragma("temp_store", &(store as i64).to_string()).await
+	}
+
+	pub async fn auto_vacuum(&mut self) -> Result<AutoVacuum> {
+		AutoVacuum::try_from(self.pragma::<i64>("auto_vacuum").await?)
+	}
+
+	/// Change the automatic vacuum mode.
+	///
+	/// Switching between `None` and any other mode only takes effect
+	/// on an empty database, or after running `VACUUM`.
+	pub async fn set_auto_vacuum(&mut self, mode: AutoVacuum) -> Result<()> {
+		self.set_pragma("auto_vacuum", &(mode as i64).to_string()).await
+	}
+
+	/// Time spent by SQLite itself waiting for a lock before returning a `Busy` error.
+	pub async fn busy_timeout(&mut self) -> Result<Duration> {
+		Ok(Duration::from_millis(self.pragma::<u64>("busy_timeout").await?))
+	}
+
+	/// Set the time spent by SQLite itself waiting for a lock before returning a `Busy` error.
+	///
+	/// The timeout is rounded down to the millisecond, and must not exceed `i32::MAX` milliseconds.
+	pub async fn set_busy_timeout(&mut self, timeout: Duration) -> Result<()> {
+		let millis = i32::try_from(timeout.as_millis()).map_err(|_| ErrorKind::InvalidArgument(format!("{:?}", timeout)).err())?;
+		self.set_pragma("busy_timeout", &millis.to_string()).await
+	}
+}
@@ -0,0 +1,126 @@
+use std::os::raw::c_void;
+use libsqlite3_sys as ffi;
+
+use crate::Result;
+use super::{
+	Connection,
+	AttachOptions,
+	SqliteError,
+	DatabaseError,
+	check_db,
+	str_to_cstring
+};
+
+impl Connection {
+	/// Serialize the database attached with the given schema name into a byte vector.
+	///
+	/// The main database schema is named `main`.
+	/// The returned bytes are a copy of the database file content,
+	/// and can be reopened later with [`Connection::deserialize`] or [`Connection::from_bytes`].
+	pub fn serialize(&self, schema: &str) -> Result<Vec<u8>> {
+		let c_schema = str_to_cstring(schema)?;
+		unsafe {
+			let mut size = 0;
+			let data = ffi::sqlite3_serialize(self.handle, c_schema.as_ptr(), &mut size, 0);
+
+			if data.is_null() {
+				// Either the schema does not exist, or the memory allocation failed.
+				if size == 0 && self.has_schema(schema) {
+					Ok(Vec::new())
+				} else {
+					Err(SqliteError::NotFound.into())
+				}
+			} else {
+				let bytes = std::slice::from_raw_parts(data, size as usize).to_vec();
+				ffi::sqlite3_free(data as *mut c_void);
+				Ok(bytes)
+			}
+		}
+	}
+
+	/// Replace the database attached with the given schema name by the given serialized database.
+	///
+	/// The database is reopened as an in-memory database holding a copy of `data`.
+	/// If `read_only` is `true`, any attempt to modify the database will fail.
+	pub fn deserialize(&mut self, schema: &str, data: &[u8], read_only: bool) -> Result<()> {
+		let c_schema = str_to_cstring(schema)?;
+		unsafe {
+			let size = data.len() as ffi::sqlite3_int64;
+			// SQLite takes ownership of the buffer, so it must be allocated by SQLite itself.
+			let buffer = ffi::sqlite3_malloc64(std::cmp::max(data.len(), 1) as ffi::sqlite3_uint64) as *mut u8;
+			if buffer.is_null() {
+				return Err(SqliteError::NoMem.into())
+			}
+
+			std::ptr::copy_nonoverlapping(data.as_ptr(), buffer, data.len());
+
+			let mut flags = ffi::SQLITE_DESERIALIZE_FREEONCLOSE;
+			if read_only {
+				flags |= ffi::SQLITE_DESERIALIZE_READONLY;
+			} else {
+				flags |= ffi::SQLITE_DESERIALIZE_RESIZEABLE;
+			}
+
+			// The buffer is freed by SQLite even if the deserialization fails.
+			check_db(self.handle, ffi::sqlite3_deserialize(self.handle, c_schema.as_ptr(), buffer, size, size, flags), None)?;
+			Ok(())
+		}
+	}
+
+	/// Open a new in-memory connection from a serialized database.
+	///
+	/// The bytes are loaded as the `main` database of the new connection.
+	pub fn from_bytes(data: &[u8]) -> Result<Connection> {
+		let mut connection = Connection::new()?;
+		connection.deserialize("main", data, false)?;
+		Ok(connection)
+	}
+
+	/// Create a new in-memory connection holding a copy of every database of this connection.
+	///
+	/// The `main` and `temp` databases are copied, and every attached database is copied into
+	/// an in-memory database attached under the same schema name (and read-only if the original is).
+	pub async fn duplicate(&mut self) -> Result<Connection> {
+		let mut connection = Connection::new()?;
+		for database in self.databases().await? {
+			match database.schema.as_str() {
+				// The `temp` database cannot be deserialized into.
+				"temp" => self.backup_to(&mut connection, "temp")?,
+				schema => {
+					let data = self.serialize(schema)?;
+					if schema != "main" {
+						connection.attach(AttachOptions::memory(), schema).await?;
+					}
+
+					connection.deserialize(schema, &data, database.read_only)?
+				}
+			}
+		}
+
+		Ok(connection)
+	}
+
+	/// Copy the database with the given schema name into the same schema of another connection.
+	fn backup_to(&self, other: &mut Connection, schema: &str) -> Result<()> {
+		let c_schema = str_to_cstring(schema)?;
+		unsafe {
+			let backup = ffi::sqlite3_backup_init(other.handle, c_schema.as_ptr(), self.handle, c_schema.as_ptr());
+			if backup.is_null() {
+				return Err(DatabaseError::last(other.handle, ffi::sqlite3_errcode(other.handle), None).into())
+			}
+
+			ffi::sqlite3_backup_step(backup, -1);
+			check_db(other.handle, ffi::sqlite3_backup_finish(backup), None)?;
+			Ok(())
+		}
+	}
+
+	fn has_schema(&self, schema: &str) -> bool {
+		match str_to_cstring(schema) {
+			Ok(c_schema) => unsafe {
+				!ffi::sqlite3_db_filename(self.handle, c_schema.as_ptr()).is_null()
+			},
+			Err(_) => false
+		}
+	}
+}
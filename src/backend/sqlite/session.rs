@@ -0,0 +1,534 @@
+use std::ffi::CStr;
+use std::os::raw::{
+	c_void,
+	c_char,
+	c_int
+};
+use std::panic::{
+	catch_unwind,
+	AssertUnwindSafe
+};
+use std::marker::PhantomData;
+use futures::future::LocalBoxFuture;
+use libsqlite3_sys as ffi;
+
+use crate::{
+	Result,
+	FromRow,
+	Value,
+	Execution
+};
+use super::{
+	Connection,
+	Statement,
+	SqliteError,
+	DatabaseError,
+	check,
+	check_db,
+	owned_value,
+	str_to_cstring
+};
+
+/// Session recording the changes made to a database through a connection.
+///
+/// A session wraps the connection it records, which is then used through the session.
+/// Every statement executed through the session on an attached table is recorded,
+/// and can be collected later as a [`Changeset`] or a patchset.
+pub struct Session<'a> {
+	connection: &'a mut Connection,
+	handle: *mut ffi::sqlite3_session
+}
+
+impl Connection {
+	/// Start a new session recording the changes made to the database with the given schema name.
+	///
+	/// The main database schema is named `main`.
+	/// No table is recorded until it is attached using [`Session::attach`].
+	pub fn session(&mut self, schema: &str) -> Result<Session<'_>> {
+		let c_schema = str_to_cstring(schema)?;
+		unsafe {
+			let mut handle = std::ptr::null_mut();
+			check_db(self.handle, ffi::sqlite3session_create(self.handle, c_schema.as_ptr(), &mut handle), None)?;
+			Ok(Session {
+				connection: self,
+				handle
+			})
+		}
+	}
+
+	/// Apply the given changeset (or patchset) to this connection.
+	///
+	/// The `conflict` function is called for each change that cannot be applied cleanly,
+	/// and decides how to resolve the conflict.
+	/// If it returns [`ConflictAction::Abort`], every change made so far is rolled back
+	/// and an `Abort` error is returned.
+	pub fn apply_changeset<F>(&mut self, changeset: &Changeset, conflict: F) -> Result<()> where F: FnMut(Conflict) -> ConflictAction {
+		let mut conflict = conflict;
+		let mut handler: &mut dyn FnMut(Conflict) -> ConflictAction = &mut conflict;
+		unsafe {
+			check_db(self.handle, ffi::sqlite3changeset_apply(
+				self.handle,
+				changeset.bytes.len() as c_int,
+				changeset.bytes.as_ptr() as *mut c_void,
+				None,
+				Some(call_conflict_handler),
+				&mut handler as *mut &mut dyn FnMut(Conflict) -> ConflictAction as *mut c_void
+			), None)?;
+			Ok(())
+		}
+	}
+}
+
+unsafe extern "C" fn call_conflict_handler(context: *mut c_void, kind: c_int, iter: *mut ffi::sqlite3_changeset_iter) -> c_int {
+	let handler = &mut *(context as *mut &mut dyn FnMut(Conflict) -> ConflictAction);
+	let result = catch_unwind(AssertUnwindSafe(|| -> std::result::Result<ConflictAction, DatabaseError> {
+		let conflict = match kind {
+			ffi::SQLITE_CHANGESET_DATA => Conflict::Data {
+				current: conflicting_values(iter)?,
+				change: Change::read(iter)?
+			},
+			ffi::SQLITE_CHANGESET_NOTFOUND => Conflict::NotFound {
+				change: Change::read(iter)?
+			},
+			ffi::SQLITE_CHANGESET_CONFLICT => Conflict::Conflict {
+				current: conflicting_values(iter)?,
+				change: Change::read(iter)?
+			},
+			ffi::SQLITE_CHANGESET_CONSTRAINT => Conflict::Constraint {
+				change: Change::read(iter)?
+			},
+			_ => {
+				let mut count = 0;
+				check(ffi::sqlite3changeset_fk_conflicts(iter, &mut count))?;
+				Conflict::ForeignKey {
+					count: count as usize
+				}
+			}
+		};
+
+		let replaceable = conflict.is_replaceable();
+		match handler(conflict) {
+			ConflictAction::Replace if !replaceable => Ok(ConflictAction::Abort),
+			action => Ok(action)
+		}
+	}));
+
+	match result {
+		Ok(Ok(action)) => action.into_raw(),
+		_ => ffi::SQLITE_CHANGESET_ABORT
+	}
+}
+
+unsafe fn conflicting_values(iter: *mut ffi::sqlite3_changeset_iter) -> std::result::Result<Vec<Value<'static>>, DatabaseError> {
+	let column_count = Change::column_count(iter)?;
+	let mut values = Vec::with_capacity(column_count);
+	for i in 0..column_count {
+		let mut value = std::ptr::null_mut();
+		check(ffi::sqlite3changeset_conflict(iter, i as c_int, &mut value))?;
+		values.push(owned_value(value));
+	}
+
+	Ok(values)
+}
+
+impl<'a> Session<'a> {
+	/// Record the changes made to the given table.
+	///
+	/// If no table name is given, every table of the database is recorded,
+	/// including tables created after this call.
+	/// Only tables with an explicit `PRIMARY KEY` are recorded.
+	pub fn attach(&mut self, table: Option<&str>) -> Result<()> {
+		unsafe {
+			match table {
+				Some(table) => {
+					let c_table = str_to_cstring(table)?;
+					check(ffi::sqlite3session_attach(self.handle, c_table.as_ptr()))?
+				},
+				None => check(ffi::sqlite3session_attach(self.handle, std::ptr::null()))?
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Checks if the session is currently recording changes.
+	pub fn is_enabled(&self) -> bool {
+		unsafe {
+			ffi::sqlite3session_enable(self.handle, -1) != 0
+		}
+	}
+
+	/// Enable or disable the recording of changes.
+	///
+	/// Sessions are enabled when created.
+	pub fn set_enabled(&mut self, enabled: bool) {
+		unsafe {
+			ffi::sqlite3session_enable(self.handle, enabled as c_int);
+		}
+	}
+
+	/// Checks if no change has been recorded yet.
+	pub fn is_empty(&self) -> bool {
+		unsafe {
+			ffi::sqlite3session_isempty(self.handle) != 0
+		}
+	}
+
+	/// Collect every change recorded so far into a changeset.
+	pub fn changeset(&mut self) -> Result<Changeset> {
+		unsafe {
+			let mut len = 0;
+			let mut data = std::ptr::null_mut();
+			check(ffi::sqlite3session_changeset(self.handle, &mut len, &mut data))?;
+			Ok(Changeset::from_raw(len, data))
+		}
+	}
+
+	/// Collect every change recorded so far into a patchset.
+	///
+	/// A patchset is a more compact changeset that only contains the primary key
+	/// of updated and deleted rows, and the new values of updated columns.
+	/// It cannot be inverted.
+	pub fn patchset(&mut self) -> Result<Changeset> {
+		unsafe {
+			let mut len = 0;
+			let mut data = std::ptr::null_mut();
+			check(ffi::sqlite3session_patchset(self.handle, &mut len, &mut data))?;
+			Ok(Changeset::from_raw(len, data))
+		}
+	}
+}
+
+impl<'a> crate::Connection for Session<'a> {
+	type Statement = Statement;
+
+	fn prepare(&mut self, sql: &str) -> Result<Option<Statement>> {
+		self.connection.prepare(sql)
+	}
+
+	fn prepare_list(&mut self, sql: &str) -> Result<Vec<Statement>> {
+		self.connection.prepare_list(sql)
+	}
+
+	fn execute_script<'s>(&'s mut self, sql: &'s str) -> LocalBoxFuture<'s, Result<()>> where Self::Statement: 's {
+		self.connection.execute_script(sql)
+	}
+
+	fn execute<'s, R: 's + FromRow>(&'s mut self, statement: &'s Statement, args: Vec<Value>) -> LocalBoxFuture<'s, Result<Execution<crate::Rows<'s, R>>>> {
+		self.connection.execute(statement, args)
+	}
+}
+
+impl<'a> crate::TransactionCapable for Session<'a> { }
+
+impl<'a> crate::SavepointCapable for Session<'a> {
+	fn anonymous_savepoint_name(&mut self) -> String {
+		self.connection.anonymous_savepoint_name()
+	}
+}
+
+impl<'a> Drop for Session<'a> {
+	fn drop(&mut self) {
+		unsafe {
+			ffi::sqlite3session_delete(self.handle);
+		}
+	}
+}
+
+/// Changeset, or patchset, produced by a [`Session`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Changeset {
+	bytes: Vec<u8>
+}
+
+impl Changeset {
+	/// Create a changeset from its binary representation.
+	pub fn from_bytes(bytes: Vec<u8>) -> Changeset {
+		Changeset {
+			bytes
+		}
+	}
+
+	/// Take ownership of a buffer allocated by SQLite.
+	unsafe fn from_raw(len: c_int, data: *mut c_void) -> Changeset {
+		let bytes = if data.is_null() || len == 0 {
+			Vec::new()
+		} else {
+			std::slice::from_raw_parts(data as *const u8, len as usize).to_vec()
+		};
+
+		ffi::sqlite3_free(data);
+		Changeset {
+			bytes
+		}
+	}
+
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.bytes
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.bytes.is_empty()
+	}
+
+	/// Compute the changeset reverting this changeset.
+	///
+	/// Patchsets cannot be inverted.
+	pub fn invert(&self) -> Result<Changeset> {
+		unsafe {
+			let mut len = 0;
+			let mut data = std::ptr::null_mut();
+			check(ffi::sqlite3changeset_invert(self.bytes.len() as c_int, self.bytes.as_ptr() as *const c_void, &mut len, &mut data))?;
+			Ok(Changeset::from_raw(len, data))
+		}
+	}
+
+	/// Combine this changeset with the given one.
+	///
+	/// Applying the result is equivalent to applying `self` then `other`.
+	/// Both must be changesets, or both must be patchsets.
+	pub fn concat(&self, other: &Changeset) -> Result<Changeset> {
+		unsafe {
+			let mut len = 0;
+			let mut data = std::ptr::null_mut();
+			check(ffi::sqlite3changeset_concat(
+				self.bytes.len() as c_int,
+				self.bytes.as_ptr() as *mut c_void,
+				other.bytes.len() as c_int,
+				other.bytes.as_ptr() as *mut c_void,
+				&mut len,
+				&mut data
+			))?;
+			Ok(Changeset::from_raw(len, data))
+		}
+	}
+
+	/// Iterate over the changes of this changeset.
+	pub fn iter(&self) -> Result<Changes<'_>> {
+		unsafe {
+			let mut handle = std::ptr::null_mut();
+			check(ffi::sqlite3changeset_start(&mut handle, self.bytes.len() as c_int, self.bytes.as_ptr() as *mut c_void))?;
+			Ok(Changes {
+				handle,
+				done: false,
+				changeset: PhantomData
+			})
+		}
+	}
+}
+
+/// Iterator over the changes of a [`Changeset`].
+pub struct Changes<'a> {
+	handle: *mut ffi::sqlite3_changeset_iter,
+	done: bool,
+	changeset: PhantomData<&'a Changeset>
+}
+
+impl<'a> Iterator for Changes<'a> {
+	type Item = Result<Change>;
+
+	fn next(&mut self) -> Option<Result<Change>> {
+		if self.done {
+			return None
+		}
+
+		unsafe {
+			match ffi::sqlite3changeset_next(self.handle) {
+				ffi::SQLITE_ROW => Some(Change::read(self.handle).map_err(Into::into)),
+				res => {
+					// The iterator cannot be resumed after the end or an error.
+					self.done = true;
+					match res {
+						ffi::SQLITE_DONE => None,
+						res => match check(res) {
+							Ok(()) => None,
+							Err(e) => Some(Err(e.into()))
+						}
+					}
+				}
+			}
+		}
+	}
+}
+
+impl<'a> Drop for Changes<'a> {
+	fn drop(&mut self) {
+		unsafe {
+			ffi::sqlite3changeset_finalize(self.handle);
+		}
+	}
+}
+
+/// Change operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+	Insert,
+	Update,
+	Delete
+}
+
+/// Single row change of a changeset.
+#[derive(Debug)]
+pub struct Change {
+	/// Name of the modified table.
+	pub table: String,
+
+	/// Operation performed on the row.
+	pub operation: Operation,
+
+	/// Whether the change was marked as indirect.
+	pub indirect: bool,
+
+	/// For each column, whether or not it is part of the table primary key.
+	pub primary_key: Vec<bool>,
+
+	/// Old values of the row.
+	///
+	/// Empty for insertions.
+	/// For updates, only primary key and modified columns are given.
+	pub old: Vec<Option<Value<'static>>>,
+
+	/// New values of the row.
+	///
+	/// Empty for deletions.
+	/// For updates, only modified columns are given.
+	pub new: Vec<Option<Value<'static>>>
+}
+
+impl Change {
+	unsafe fn column_count(iter: *mut ffi::sqlite3_changeset_iter) -> std::result::Result<usize, DatabaseError> {
+		let mut table: *const c_char = std::ptr::null();
+		let mut column_count = 0;
+		let mut op = 0;
+		let mut indirect = 0;
+		check(ffi::sqlite3changeset_op(iter, &mut table, &mut column_count, &mut op, &mut indirect))?;
+		Ok(column_count as usize)
+	}
+
+	unsafe fn read(iter: *mut ffi::sqlite3_changeset_iter) -> std::result::Result<Change, DatabaseError> {
+		let mut table: *const c_char = std::ptr::null();
+		let mut column_count = 0;
+		let mut op = 0;
+		let mut indirect = 0;
+		check(ffi::sqlite3changeset_op(iter, &mut table, &mut column_count, &mut op, &mut indirect))?;
+
+		let operation = match op {
+			ffi::SQLITE_INSERT => Operation::Insert,
+			ffi::SQLITE_UPDATE => Operation::Update,
+			ffi::SQLITE_DELETE => Operation::Delete,
+			_ => return Err(SqliteError::Corrupt.into())
+		};
+
+		let mut pk: *mut u8 = std::ptr::null_mut();
+		let mut pk_len = 0;
+		check(ffi::sqlite3changeset_pk(iter, &mut pk, &mut pk_len))?;
+		let primary_key = std::slice::from_raw_parts(pk, pk_len as usize).iter().map(|b| *b != 0).collect();
+
+		let read_values = |f: unsafe extern "C" fn(*mut ffi::sqlite3_changeset_iter, c_int, *mut *mut ffi::sqlite3_value) -> c_int| {
+			let mut values = Vec::with_capacity(column_count as usize);
+			for i in 0..column_count {
+				let mut value = std::ptr::null_mut();
+				check(f(iter, i, &mut value))?;
+				if value.is_null() {
+					values.push(None)
+				} else {
+					values.push(Some(owned_value(value)))
+				}
+			}
+
+			Ok::<_, DatabaseError>(values)
+		};
+
+		let old = match operation {
+			Operation::Insert => Vec::new(),
+			_ => read_values(ffi::sqlite3changeset_old)?
+		};
+
+		let new = match operation {
+			Operation::Delete => Vec::new(),
+			_ => read_values(ffi::sqlite3changeset_new)?
+		};
+
+		Ok(Change {
+			table: CStr::from_ptr(table).to_string_lossy().into_owned(),
+			operation,
+			indirect: indirect != 0,
+			primary_key,
+			old,
+			new
+		})
+	}
+}
+
+/// Conflict raised while applying a changeset.
+#[derive(Debug)]
+pub enum Conflict {
+	/// The row to update or delete exists, but its current values
+	/// do not match the expected old values.
+	Data {
+		change: Change,
+		current: Vec<Value<'static>>
+	},
+
+	/// The row to update or delete does not exist.
+	NotFound {
+		change: Change
+	},
+
+	/// The row to insert already exists.
+	Conflict {
+		change: Change,
+		current: Vec<Value<'static>>
+	},
+
+	/// Applying the change would violate a constraint.
+	Constraint {
+		change: Change
+	},
+
+	/// Applying the changeset left foreign key constraint violations.
+	///
+	/// This is raised once, after every change has been applied.
+	ForeignKey {
+		count: usize
+	}
+}
+
+impl Conflict {
+	/// Checks if the conflict can be resolved with [`ConflictAction::Replace`].
+	pub fn is_replaceable(&self) -> bool {
+		matches!(self, Conflict::Data { .. } | Conflict::Conflict { .. })
+	}
+}
+
+/// How to resolve a changeset conflict.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictAction {
+	/// Skip the conflicting change.
+	///
+	/// For foreign key conflicts, the changeset is committed anyway.
+	Omit,
+
+	/// Overwrite the conflicting row with the change.
+	///
+	/// This is only valid for [`Conflict::Data`] and [`Conflict::Conflict`].
+	/// It is treated as `Abort` for any other conflict.
+	Replace,
+
+	/// Stop and roll back every change applied so far.
+	Abort
+}
+
+impl ConflictAction {
+	fn into_raw(self) -> c_int {
+		match self {
+			ConflictAction::Omit => ffi::SQLITE_CHANGESET_OMIT,
+			ConflictAction::Replace => ffi::SQLITE_CHANGESET_REPLACE,
+			ConflictAction::Abort => ffi::SQLITE_CHANGESET_ABORT
+		}
+	}
+}
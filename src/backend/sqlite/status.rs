@@ -0,0 +1,383 @@
+use std::ops::Sub;
+use std::os::raw::c_int;
+use libsqlite3_sys as ffi;
+
+use crate::Result;
+use super::{
+	Connection,
+	Statement,
+	check
+};
+
+/// Performance counter of a statement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatementCounter {
+	/// Number of steps taken by full table scans.
+	FullscanStep,
+
+	/// Number of sort operations.
+	Sort,
+
+	/// Number of rows inserted into automatic indexes.
+	AutoIndex,
+
+	/// Number of virtual machine operations.
+	VmStep,
+
+	/// Number of times the statement was automatically recompiled after a schema change.
+	Reprepare,
+
+	/// Number of times the statement was run.
+	Run,
+
+	/// Approximate number of bytes of heap memory used to store the statement.
+	///
+	/// This counter cannot be reset.
+	MemUsed
+}
+
+impl StatementCounter {
+	fn into_raw(self) -> c_int {
+		match self {
+			StatementCounter::FullscanStep => ffi::SQLITE_STMTSTATUS_FULLSCAN_STEP,
+			StatementCounter::Sort => ffi::SQLITE_STMTSTATUS_SORT,
+			StatementCounter::AutoIndex => ffi::SQLITE_STMTSTATUS_AUTOINDEX,
+			StatementCounter::VmStep => ffi::SQLITE_STMTSTATUS_VM_STEP,
+			StatementCounter::Reprepare => ffi::SQLITE_STMTSTATUS_REPREPARE,
+			StatementCounter::Run => ffi::SQLITE_STMTSTATUS_RUN,
+			StatementCounter::MemUsed => ffi::SQLITE_STMTSTATUS_MEMUSED
+		}
+	}
+}
+
+/// Snapshot of every performance counter of a statement.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct StatementStatus {
+	pub fullscan_steps: u64,
+	pub sorts: u64,
+	pub autoindex: u64,
+	pub vm_steps: u64,
+	pub reprepares: u64,
+	pub runs: u64,
+	pub memory_used: u64
+}
+
+/// Performance counters.
+impl Statement {
+	/// Value of the given counter.
+	///
+	/// If `reset` is `true`, the counter is reset to zero after being read.
+	pub fn counter(&self, counter: StatementCounter, reset: bool) -> u64 {
+		unsafe {
+			ffi::sqlite3_stmt_status(self.handle, counter.into_raw(), reset as c_int) as u64
+		}
+	}
+
+	/// Value of every counter.
+	///
+	/// If `reset` is `true`, the counters are reset to zero after being read.
+	pub fn status(&self, reset: bool) -> StatementStatus {
+		StatementStatus {
+			fullscan_steps: self.counter(StatementCounter::FullscanStep, reset),
+			sorts: self.counter(StatementCounter::Sort, reset),
+			autoindex: self.counter(StatementCounter::AutoIndex, reset),
+			vm_steps: self.counter(StatementCounter::VmStep, reset),
+			reprepares: self.counter(StatementCounter::Reprepare, reset),
+			runs: self.counter(StatementCounter::Run, reset),
+			memory_used: self.counter(StatementCounter::MemUsed, false)
+		}
+	}
+}
+
+/// Current and highest recorded value of a resource metric.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Metric {
+	pub current: i64,
+	pub highwater: i64
+}
+
+impl Sub for Metric {
+	type Output = Metric;
+
+	fn sub(self, other: Metric) -> Metric {
+		Metric {
+			current: self.current - other.current,
+			highwater: self.highwater - other.highwater
+		}
+	}
+}
+
+/// Snapshot of the resources used by a connection.
+///
+/// Memory metrics are in bytes.
+/// Snapshots can be subtracted to compute how the metrics evolved between two points in time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ConnectionStatus {
+	/// Number of lookaside memory slots in use.
+	pub lookaside_used: Metric,
+
+	/// Number of allocations satisfied from lookaside memory (highwater only).
+	pub lookaside_hit: Metric,
+
+	/// Number of allocations too large for lookaside memory (highwater only).
+	pub lookaside_miss_size: Metric,
+
+	/// Number of allocations not satisfied because lookaside memory was full (highwater only).
+	pub lookaside_miss_full: Metric,
+
+	/// Heap memory used by the pager caches.
+	pub cache_used: Metric,
+
+	/// Number of pager cache hits.
+	pub cache_hit: Metric,
+
+	/// Number of pager cache misses.
+	pub cache_miss: Metric,
+
+	/// Number of dirty cache pages written to disk.
+	pub cache_write: Metric,
+
+	/// Number of dirty cache pages written to disk in the middle of a transaction.
+	pub cache_spill: Metric,
+
+	/// Heap memory used to store the schemas.
+	pub schema_used: Metric,
+
+	/// Heap memory used by prepared statements.
+	pub statement_used: Metric,
+
+	/// Whether there are unresolved deferred foreign key constraints (`current` is `1`) or not (`0`).
+	pub deferred_foreign_keys: Metric
+}
+
+impl Sub for ConnectionStatus {
+	type Output = ConnectionStatus;
+
+	fn sub(self, other: ConnectionStatus) -> ConnectionStatus {
+		ConnectionStatus {
+			lookaside_used: self.lookaside_used - other.lookaside_used,
+			lookaside_hit: self.lookaside_hit - other.lookaside_hit,
+			lookaside_miss_size: self.lookaside_miss_size - other.lookaside_miss_size,
+			lookaside_miss_full: self.lookaside_miss_full - other.lookaside_miss_full,
+			cache_used: self.cache_used - other.cache_used,
+			cache_hit: self.cache_hit - other.cache_hit,
+			cache_miss: self.cache_miss - other.cache_miss,
+			cache_write: self.cache_write - other.cache_write,
+			cache_spill: self.cache_spill - other.cache_spill,
+			schema_used: self.schema_used - other.schema_used,
+			statement_used: self.statement_used - other.statement_used,
+			deferred_foreign_keys: self.deferred_foreign_keys - other.deferred_foreign_keys
+		}
+	}
+}
+
+impl Connection {
+	fn status_metric(&self, op: c_int, reset: bool) -> Result<Metric> {
+		let mut current = 0;
+		let mut highwater = 0;
+		unsafe {
+			check(ffi::sqlite3_db_status(self.handle, op, &mut current, &mut highwater, reset as c_int))?;
+		}
+
+		Ok(Metric {
+			current: current as i64,
+			highwater: highwater as i64
+		})
+	}
+
+	/// Snapshot of the resources used by this connection.
+	///
+	/// If `reset` is `true`, highwater marks are reset to the current values,
+	/// and cumulative counters (hits, misses, writes and spills) are reset to zero.
+	pub fn status(&self, reset: bool) -> Result<ConnectionStatus> {
+		Ok(ConnectionStatus {
+			lookaside_used: self.status_metric(ffi::SQLITE_DBSTATUS_LOOKASIDE_USED, reset)?,
+			lookaside_hit: self.status_metric(ffi::SQLITE_DBSTATUS_LOOKASIDE_HIT, reset)?,
+			lookaside_miss_size: self.status_metric(ffi::SQLITE_DBSTATUS_LOOKASIDE_MISS_SIZE, reset)?,
+			lookaside_miss_full: self.status_metric(ffi::SQLITE_DBSTATUS_LOOKASIDE_MISS_FULL, reset)?,
+			cache_used: self.status_metric(ffi::SQLITE_DBSTATUS_CACHE_USED, reset)?,
+			cache_hit: self.status_metric(ffi::SQLITE_DBSTATUS_CACHE_HIT, reset)?,
+			cache_miss: self.status_metric(ffi::SQLITE_DBSTATUS_CACHE_MISS, reset)?,
+			cache_write: self.status_metric(ffi::SQLITE_DBSTATUS_CACHE_WRITE, reset)?,
+			cache_spill: self.status_metric(ffi::SQLITE_DBSTATUS_CACHE_SPILL, reset)?,
+			schema_used: self.status_metric(ffi::SQLITE_DBSTATUS_SCHEMA_USED, reset)?,
+			statement_used: self.status_metric(ffi::SQLITE_DBSTATUS_STMT_USED, reset)?,
+			deferred_foreign_keys: self.status_metric(ffi::SQLITE_DBSTATUS_DEFERRED_FKS, reset)?
+		})
+	}
+}
+
+/// Snapshot of the resources used by the SQLite library, for every connection.
+///
+/// Memory metrics are in bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LibraryStatus {
+	/// Heap memory in use.
+	pub memory_used: Metric,
+
+	/// Number of separate memory allocations in use.
+	pub malloc_count: Metric,
+
+	/// Largest memory allocation requested (highwater only).
+	pub malloc_size: Metric,
+
+	/// Number of pages used by the page cache memory pool.
+	pub pagecache_used: Metric,
+
+	/// Page cache memory allocated from the heap because the pool was full.
+	pub pagecache_overflow: Metric,
+
+	/// Largest page cache allocation requested (highwater only).
+	pub pagecache_size: Metric,
+
+	/// Deepest parser stack (highwater only).
+	pub parser_stack: Metric
+}
+
+impl Sub for LibraryStatus {
+	type Output = LibraryStatus;
+
+	fn sub(self, other: LibraryStatus) -> LibraryStatus {
+		LibraryStatus {
+			memory_used: self.memory_used - other.memory_used,
+			malloc_count: self.malloc_count - other.malloc_count,
+			malloc_size: self.malloc_size - other.malloc_size,
+			pagecache_used: self.pagecache_used - other.pagecache_used,
+			pagecache_overflow: self.pagecache_overflow - other.pagecache_overflow,
+			pagecache_size: self.pagecache_size - other.pagecache_size,
+			parser_stack: self.parser_stack - other.parser_stack
+		}
+	}
+}
+
+fn library_metric(op: c_int, reset: bool) -> Result<Metric> {
+	let mut current = 0;
+	let mut highwater = 0;
+	unsafe {
+		check(ffi::sqlite3_status64(op, &mut current, &mut highwater, reset as c_int))?;
+	}
+
+	Ok(Metric {
+		current,
+		highwater
+	})
+}
+
+/// Snapshot of the resources used by the SQLite library.
+///
+/// If `reset` is `true`, highwater marks are reset to the current values.
+pub fn library_status(reset: bool) -> Result<LibraryStatus> {
+	Ok(LibraryStatus {
+		memory_used: library_metric(ffi::SQLITE_STATUS_MEMORY_USED, reset)?,
+		malloc_count: library_metric(ffi::SQLITE_STATUS_MALLOC_COUNT, reset)?,
+		malloc_size: library_metric(ffi::SQLITE_STATUS_MALLOC_SIZE, reset)?,
+		pagecache_used: library_metric(ffi::SQLITE_STATUS_PAGECACHE_USED, reset)?,
+		pagecache_overflow: library_metric(ffi::SQLITE_STATUS_PAGECACHE_OVERFLOW, reset)?,
+		pagecache_size: library_metric(ffi::SQLITE_STATUS_PAGECACHE_SIZE, reset)?,
+		parser_stack: library_metric(ffi::SQLITE_STATUS_PARSER_STACK, reset)?
+	})
+}
+
+#[cfg(feature = "scanstatus")]
+pub use scan::*;
+
+/// Per-loop scan statistics.
+///
+/// This requires SQLite 3.43 or later, compiled with `SQLITE_ENABLE_STMT_SCANSTATUS`.
+#[cfg(feature = "scanstatus")]
+mod scan {
+	use std::ffi::CStr;
+	use std::os::raw::{
+		c_void,
+		c_char,
+		c_int
+	};
+	use libsqlite3_sys as ffi;
+
+	use super::super::Statement;
+
+	const SQLITE_SCANSTAT_PARENTID: c_int = 6;
+	const SQLITE_SCANSTAT_NCYCLE: c_int = 7;
+	const SQLITE_SCANSTAT_COMPLEX: c_int = 1;
+
+	extern "C" {
+		fn sqlite3_stmt_scanstatus_v2(stmt: *mut ffi::sqlite3_stmt, idx: c_int, op: c_int, flags: c_int, out: *mut c_void) -> c_int;
+	}
+
+	/// Statistics of a single loop of a statement.
+	#[derive(Clone, Debug, PartialEq)]
+	pub struct ScanStatus {
+		/// Number of times the loop was run.
+		pub loops: i64,
+
+		/// Number of rows visited by the loop.
+		pub visits: i64,
+
+		/// Number of rows per loop run estimated by the query planner.
+		pub estimated_rows: f64,
+
+		/// Name of the scanned table or index.
+		pub name: Option<String>,
+
+		/// `EXPLAIN QUERY PLAN` description of the loop.
+		pub explain: Option<String>,
+
+		/// `EXPLAIN QUERY PLAN` node identifier of the loop.
+		pub select_id: i64,
+
+		/// `EXPLAIN QUERY PLAN` node identifier of the parent of the loop.
+		pub parent_id: i64,
+
+		/// Number of cycles spent in the loop, if `SQLITE_ENABLE_STMT_SCANSTATUS` cycle counting is supported.
+		pub cycles: i64
+	}
+
+	impl Statement {
+		unsafe fn scan_value<T: Default>(&self, index: usize, op: c_int) -> Option<T> {
+			let mut value = T::default();
+			if sqlite3_stmt_scanstatus_v2(self.handle, index as c_int, op, SQLITE_SCANSTAT_COMPLEX, &mut value as *mut T as *mut c_void) == 0 {
+				Some(value)
+			} else {
+				None
+			}
+		}
+
+		unsafe fn scan_string(&self, index: usize, op: c_int) -> Option<String> {
+			let mut value: *const c_char = std::ptr::null();
+			if sqlite3_stmt_scanstatus_v2(self.handle, index as c_int, op, SQLITE_SCANSTAT_COMPLEX, &mut value as *mut *const c_char as *mut c_void) == 0 && !value.is_null() {
+				Some(CStr::from_ptr(value).to_string_lossy().into_owned())
+			} else {
+				None
+			}
+		}
+
+		/// Statistics of each loop of the statement, since it was prepared or since the last reset.
+		pub fn scan_status(&self) -> Vec<ScanStatus> {
+			let mut loops = Vec::new();
+			unsafe {
+				while let Some(count) = self.scan_value::<i64>(loops.len(), ffi::SQLITE_SCANSTAT_NLOOP) {
+					let i = loops.len();
+					loops.push(ScanStatus {
+						loops: count,
+						visits: self.scan_value(i, ffi::SQLITE_SCANSTAT_NVISIT).unwrap_or(0),
+						estimated_rows: self.scan_value(i, ffi::SQLITE_SCANSTAT_EST).unwrap_or(0.0),
+						name: self.scan_string(i, ffi::SQLITE_SCANSTAT_NAME),
+						explain: self.scan_string(i, ffi::SQLITE_SCANSTAT_EXPLAIN),
+						select_id: self.scan_value::<c_int>(i, ffi::SQLITE_SCANSTAT_SELECTID).unwrap_or(0) as i64,
+						parent_id: self.scan_value::<c_int>(i, SQLITE_SCANSTAT_PARENTID).unwrap_or(0) as i64,
+						cycles: self.scan_value(i, SQLITE_SCANSTAT_NCYCLE).unwrap_or(0)
+					})
+				}
+			}
+
+			loops
+		}
+
+		/// Reset the scan statistics of the statement.
+		pub fn reset_scan_status(&self) {
+			unsafe {
+				ffi::sqlite3_stmt_scanstatus_reset(self.handle)
+			}
+		}
+	}
+}
@@ -0,0 +1,175 @@
+use std::ffi::CStr;
+use std::ops::BitOr;
+use std::os::raw::{
+	c_void,
+	c_char,
+	c_int,
+	c_uint
+};
+use std::panic::{
+	catch_unwind,
+	AssertUnwindSafe
+};
+use std::time::Duration;
+use futures::channel::mpsc;
+use libsqlite3_sys as ffi;
+
+use super::Connection;
+
+pub(super) type TraceCallback = Box<dyn FnMut(TraceEvent) + Send>;
+
+/// Set of trace events to listen to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TraceMask(c_uint);
+
+impl TraceMask {
+	/// Statement starts running.
+	pub const STATEMENT: TraceMask = TraceMask(ffi::SQLITE_TRACE_STMT);
+
+	/// Statement finishes, with its running time.
+	pub const PROFILE: TraceMask = TraceMask(ffi::SQLITE_TRACE_PROFILE);
+
+	/// Statement produces a row.
+	pub const ROW: TraceMask = TraceMask(ffi::SQLITE_TRACE_ROW);
+
+	/// Connection closes.
+	pub const CLOSE: TraceMask = TraceMask(ffi::SQLITE_TRACE_CLOSE);
+
+	pub const ALL: TraceMask = TraceMask(ffi::SQLITE_TRACE_STMT | ffi::SQLITE_TRACE_PROFILE | ffi::SQLITE_TRACE_ROW | ffi::SQLITE_TRACE_CLOSE);
+
+	pub fn contains(&self, other: TraceMask) -> bool {
+		self.0 & other.0 == other.0
+	}
+}
+
+impl BitOr for TraceMask {
+	type Output = TraceMask;
+
+	fn bitor(self, other: TraceMask) -> TraceMask {
+		TraceMask(self.0 | other.0)
+	}
+}
+
+/// Trace event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceEvent {
+	/// A statement starts running.
+	///
+	/// The SQL text has its parameters expanded to their bound values.
+	/// For statements run by triggers, it is a comment naming the trigger.
+	Statement {
+		sql: String
+	},
+
+	/// A statement finished running.
+	Profile {
+		sql: String,
+
+		/// Running time of the statement, with a nanosecond precision.
+		duration: Duration
+	},
+
+	/// A statement produced a row.
+	Row,
+
+	/// The connection is closing.
+	Close
+}
+
+unsafe fn statement_sql(stmt: *mut ffi::sqlite3_stmt) -> String {
+	let sql = ffi::sqlite3_sql(stmt);
+	if sql.is_null() {
+		String::new()
+	} else {
+		CStr::from_ptr(sql).to_string_lossy().into_owned()
+	}
+}
+
+unsafe fn expanded_sql(stmt: *mut ffi::sqlite3_stmt) -> String {
+	let sql = ffi::sqlite3_expanded_sql(stmt);
+	if sql.is_null() {
+		// The expanded text is too long, or memory allocation failed.
+		statement_sql(stmt)
+	} else {
+		let string = CStr::from_ptr(sql).to_string_lossy().into_owned();
+		ffi::sqlite3_free(sql as *mut c_void);
+		string
+	}
+}
+
+unsafe extern "C" fn call_trace_callback(kind: c_uint, context: *mut c_void, p: *mut c_void, x: *mut c_void) -> c_int {
+	let callback = &mut *(context as *mut TraceCallback);
+	let event = match kind {
+		ffi::SQLITE_TRACE_STMT => {
+			let unexpanded = x as *const c_char;
+			let sql = if !unexpanded.is_null() && CStr::from_ptr(unexpanded).to_bytes().starts_with(b"--") {
+				CStr::from_ptr(unexpanded).to_string_lossy().into_owned()
+			} else {
+				expanded_sql(p as *mut ffi::sqlite3_stmt)
+			};
+
+			TraceEvent::Statement {
+				sql
+			}
+		},
+		ffi::SQLITE_TRACE_PROFILE => TraceEvent::Profile {
+			sql: statement_sql(p as *mut ffi::sqlite3_stmt),
+			duration: Duration::from_nanos(*(x as *const i64) as u64)
+		},
+		ffi::SQLITE_TRACE_ROW => TraceEvent::Row,
+		ffi::SQLITE_TRACE_CLOSE => TraceEvent::Close,
+		_ => return 0
+	};
+
+	// Panics must not unwind through SQLite.
+	catch_unwind(AssertUnwindSafe(|| callback(event))).ok();
+	0
+}
+
+/// Statement tracing.
+///
+/// A connection holds at most one trace callback at a time:
+/// each of the following functions replaces the previous callback.
+impl Connection {
+	/// Call the given function on every traced event selected by `mask`.
+	pub fn trace<F>(&mut self, mask: TraceMask, callback: F) where F: 'static + Send + FnMut(TraceEvent) {
+		let mut callback: Box<TraceCallback> = Box::new(Box::new(callback));
+		unsafe {
+			ffi::sqlite3_trace_v2(self.handle, mask.0, Some(call_trace_callback), &mut *callback as *mut TraceCallback as *mut c_void);
+		}
+
+		self.trace = Some(callback);
+	}
+
+	/// Send every traced event selected by `mask` into the returned stream.
+	///
+	/// The stream ends when the connection is closed or the trace callback replaced.
+	pub fn trace_stream(&mut self, mask: TraceMask) -> mpsc::UnboundedReceiver<TraceEvent> {
+		let (sender, receiver) = mpsc::unbounded();
+		self.trace(mask, move |event| {
+			sender.unbounded_send(event).ok();
+		});
+		receiver
+	}
+
+	/// Call the given function with the SQL text and running time of
+	/// every statement running for at least `threshold`.
+	pub fn log_slow_queries<F>(&mut self, threshold: Duration, mut log: F) where F: 'static + Send + FnMut(&str, Duration) {
+		self.trace(TraceMask::PROFILE, move |event| {
+			if let TraceEvent::Profile { sql, duration } = event {
+				if duration >= threshold {
+					log(&sql, duration)
+				}
+			}
+		})
+	}
+
+	/// Remove the trace callback.
+	pub fn untrace(&mut self) {
+		unsafe {
+			ffi::sqlite3_trace_v2(self.handle, 0, None, std::ptr::null_mut());
+		}
+
+		self.trace = None;
+	}
+}
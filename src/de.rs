@@ -0,0 +1,279 @@
+//! Row deserialization using serde.
+use mown::Mown;
+use serde::de::{
+	self,
+	DeserializeOwned,
+	Deserializer,
+	IntoDeserializer,
+	Visitor,
+	MapAccess,
+	SeqAccess
+};
+use crate::{
+	Value,
+	FromRow,
+	RowValues,
+	Result,
+	Error,
+	ErrorKind
+};
+
+/// Deserialize a row into the given type.
+///
+/// Structs and maps are deserialized using the column names as keys,
+/// sequences and tuples using the columns in order.
+/// A single-column row can be deserialized as its only value.
+///
+/// Text columns holding JSON can be deserialized into nested structures.
+pub fn from_row<'a, T: DeserializeOwned, R: RowValues<'a>>(row: R) -> Result<T> {
+	let names = (0..row.column_count()).map(|i| row.column_name(i).unwrap_or_default().to_string()).collect();
+	T::deserialize(RowDeserializer {
+		names,
+		values: row.collect()
+	})
+}
+
+/// Deserialize a value into the given type.
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T> {
+	T::deserialize(ValueDeserializer(value))
+}
+
+/// Row deserialized using serde.
+///
+/// Row streams yield a `Conversion` error if the row cannot be deserialized,
+/// whereas [`FromRow::from`] panics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Deserialized<T>(pub T);
+
+wrapper!(Deserialized);
+
+impl<T: DeserializeOwned> FromRow for Deserialized<T> {
+	fn from<'a, R: RowValues<'a>>(row: R) -> Self {
+		match Self::try_from_row(row) {
+			Ok(t) => t,
+			Err(e) => panic!("invalid conversion: {}", e)
+		}
+	}
+
+	fn try_from_row<'a, R: RowValues<'a>>(row: R) -> Result<Self> {
+		from_row(row).map(Deserialized)
+	}
+}
+
+struct RowDeserializer<'a> {
+	names: Vec<String>,
+	values: Vec<Value<'a>>
+}
+
+impl<'a> RowDeserializer<'a> {
+	/// Only value of a single-column row.
+	fn single(mut self) -> Result<ValueDeserializer<'a>> {
+		if self.values.len() == 1 {
+			Ok(ValueDeserializer(self.values.pop().unwrap()))
+		} else {
+			Err(ErrorKind::Conversion(format!("expected a single column, found {}", self.values.len())).err())
+		}
+	}
+}
+
+macro_rules! forward_to_single {
+	($($method:ident)*) => {
+		$(
+			fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+				self.single()?.$method(visitor)
+			}
+		)*
+	};
+}
+
+impl<'de, 'a> Deserializer<'de> for RowDeserializer<'a> {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		self.deserialize_map(visitor)
+	}
+
+	forward_to_single! {
+		deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+		deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_f32 deserialize_f64
+		deserialize_char deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf
+		deserialize_option deserialize_unit deserialize_identifier
+	}
+
+	fn deserialize_unit_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value> {
+		self.single()?.deserialize_unit_struct(name, visitor)
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value> {
+		self.single()?.deserialize_enum(name, variants, visitor)
+	}
+
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_seq(Columns {
+			values: self.values.into_iter()
+		})
+	}
+
+	fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_map(NamedColumns {
+			columns: self.names.into_iter().zip(self.values),
+			value: None
+		})
+	}
+
+	fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, _fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+		self.deserialize_map(visitor)
+	}
+
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_unit()
+	}
+}
+
+struct Columns<'a> {
+	values: std::vec::IntoIter<Value<'a>>
+}
+
+impl<'de, 'a> SeqAccess<'de> for Columns<'a> {
+	type Error = Error;
+
+	fn next_element_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+		match self.values.next() {
+			Some(value) => seed.deserialize(ValueDeserializer(value)).map(Some),
+			None => Ok(None)
+		}
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.values.len())
+	}
+}
+
+struct NamedColumns<'a, I> {
+	columns: I,
+	value: Option<Value<'a>>
+}
+
+impl<'de, 'a, I: Iterator<Item = (String, Value<'a>)>> MapAccess<'de> for NamedColumns<'a, I> {
+	type Error = Error;
+
+	fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+		match self.columns.next() {
+			Some((name, value)) => {
+				self.value = Some(value);
+				seed.deserialize(name.into_deserializer()).map(Some)
+			},
+			None => Ok(None)
+		}
+	}
+
+	fn next_value_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value> {
+		let value = self.value.take().expect("value requested before key");
+		seed.deserialize(ValueDeserializer(value))
+	}
+}
+
+/// Deserializer of a single value.
+struct ValueDeserializer<'a>(Value<'a>);
+
+impl<'a> ValueDeserializer<'a> {
+	fn invalid_type<E: de::Expected>(&self, expected: &E) -> Error {
+		let unexpected = match &self.0 {
+			Value::Integer(i) => de::Unexpected::Signed(*i),
+			Value::Float(f) => de::Unexpected::Float(*f),
+			Value::Text(str) => de::Unexpected::Str(str),
+			Value::Blob(bytes) => de::Unexpected::Bytes(bytes),
+			Value::Null => de::Unexpected::Unit
+		};
+
+		de::Error::invalid_type(unexpected, expected)
+	}
+
+	/// Nested structure, encoded in JSON.
+	fn json<E: de::Expected>(&self, expected: &E) -> Result<serde_json::Value> {
+		match &self.0 {
+			Value::Text(text) => Ok(serde_json::from_str(text)?),
+			_ => Err(self.invalid_type(expected))
+		}
+	}
+}
+
+macro_rules! forward_to_json {
+	($($method:ident ( $($arg:ident : $ty:ty),* ))*) => {
+		$(
+			fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value> {
+				Ok(self.json(&visitor)?.$method($($arg,)* visitor)?)
+			}
+		)*
+	};
+}
+
+impl<'de, 'a> Deserializer<'de> for ValueDeserializer<'a> {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		match self.0 {
+			Value::Integer(i) => visitor.visit_i64(i),
+			Value::Float(f) => visitor.visit_f64(f),
+			Value::Text(Mown::Borrowed(str)) => visitor.visit_str(str),
+			Value::Text(Mown::Owned(str)) => visitor.visit_string(str),
+			Value::Blob(Mown::Borrowed(bytes)) => visitor.visit_bytes(bytes),
+			Value::Blob(Mown::Owned(bytes)) => visitor.visit_byte_buf(bytes),
+			Value::Null => visitor.visit_unit()
+		}
+	}
+
+	fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		match self.0 {
+			Value::Integer(i) => visitor.visit_bool(i != 0),
+			_ => self.deserialize_any(visitor)
+		}
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		if self.0.is_null() {
+			visitor.visit_none()
+		} else {
+			visitor.visit_some(self)
+		}
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value> {
+		match &self.0 {
+			// Unit variant.
+			Value::Text(text) if !text.trim_start().starts_with('{') => {
+				visitor.visit_enum(text.to_string().into_deserializer())
+			},
+			_ => Ok(self.json(&visitor)?.deserialize_enum(name, variants, visitor)?)
+		}
+	}
+
+	forward_to_json! {
+		deserialize_seq()
+		deserialize_tuple(len: usize)
+		deserialize_tuple_struct(name: &'static str, len: usize)
+		deserialize_map()
+		deserialize_struct(name: &'static str, fields: &'static [&'static str])
+	}
+
+	serde::forward_to_deserialize_any! {
+		i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit unit_struct identifier ignored_any
+	}
+}
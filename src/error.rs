@@ -21,6 +21,14 @@ impl Error {
 		&self.kind
 	}
 
+	/// Backend error that caused this error, if any.
+	///
+	/// It can be downcasted to the backend error type,
+	/// such as [`sqlite::DatabaseError`](crate::sqlite::DatabaseError).
+	pub fn get_ref(&self) -> Option<&(dyn Send + Sync + std::error::Error + 'static)> {
+		self.source.as_deref()
+	}
+
 	// pub fn backoff(self) -> backoff::Error<Error> {
 	// 	if self.kind.is_busy() {
 	// 		backoff::Error::Transient(self)
@@ -30,21 +38,107 @@ impl Error {
 	// }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ErrorKind {
 	InvalidString(String),
 	InvalidPath(PathBuf),
 	InvalidQuery,
+
+	/// An argument given to a function is out of its accepted range.
+	InvalidArgument(String),
+
+	/// A value cannot be converted from or into the requested type.
+	Conversion(String),
+
+	/// Generic error, when no other kind applies.
 	Failure,
 
+	/// Internal malfunction of the database engine.
+	Internal,
+
 	/// The database is busy.
+	///
+	/// Another connection is holding a lock on the database.
+	/// The operation may succeed if retried later.
 	Busy,
 
+	/// A table is locked.
+	///
+	/// The conflict comes from the same connection (or a connection sharing the same cache),
+	/// so retrying will not help until the conflicting statement is finished.
+	Locked,
+
+	/// An attempt was made to write to a read-only database.
+	ReadOnly,
+
+	/// The operation was interrupted.
+	Interrupted,
+
+	/// The operation was aborted, for instance by a rollback.
+	Aborted,
+
+	/// A memory allocation failed.
+	OutOfMemory,
+
+	/// An I/O error occurred while reading or writing the database file.
+	Io,
+
+	/// The database file is corrupted.
+	Corrupt,
+
+	/// The database file is not a database.
+	NotADatabase,
+
+	/// The disk is full.
+	DiskFull,
+
+	/// The database file cannot be opened.
+	CantOpen,
+
+	/// The requested access mode is not permitted.
+	PermissionDenied,
+
+	/// The operation was denied by the authorizer.
+	Unauthorized,
+
 	/// The database schema changed since the statement was prepared.
 	SchemaChanged,
 
+	/// A string or blob is too large, or a statement is too long.
+	TooBig,
+
 	/// An SQL constraint violation occurred while trying to process an SQL statement.
-	ConstraintViolation
+	ConstraintViolation,
+
+	/// A value does not have the expected type.
+	TypeMismatch,
+
+	/// The database library was used incorrectly.
+	Misuse,
+
+	/// A parameter index is out of range.
+	OutOfRange,
+
+	/// A query expected to return a row returned none.
+	RowNotFound,
+
+	/// A query expected to return at most one row returned more.
+	TooManyRows,
+
+	/// A single statement was expected, but the SQL text contains more than one.
+	MultipleStatements,
+
+	/// The number of parameters of a statement does not match the expected number.
+	ParameterCountMismatch {
+		expected: usize,
+		found: usize
+	},
+
+	/// The number of columns returned by a statement does not match the expected number.
+	ColumnCountMismatch {
+		expected: usize,
+		found: usize
+	}
 }
 
 impl ErrorKind {
@@ -56,10 +150,11 @@ impl ErrorKind {
 	}
 
 	pub fn is_busy(&self) -> bool {
-		match self {
-			ErrorKind::Busy => true,
-			_ => false
-		}
+		matches!(self, ErrorKind::Busy)
+	}
+
+	pub fn is_locked(&self) -> bool {
+		matches!(self, ErrorKind::Locked)
 	}
 }
 
@@ -70,16 +165,74 @@ impl fmt::Display for ErrorKind {
 			InvalidString(_) => write!(f, "invalid string"),
 			InvalidPath(_) => write!(f, "invalid path"),
 			InvalidQuery => write!(f, "invalid query"),
+			InvalidArgument(_) => write!(f, "invalid argument"),
+			Conversion(msg) => write!(f, "conversion error: {}", msg),
 			Failure => write!(f, "failure"),
+			Internal => write!(f, "internal error"),
 			Busy => write!(f, "busy"),
+			Locked => write!(f, "locked"),
+			ReadOnly => write!(f, "read-only database"),
+			Interrupted => write!(f, "interrupted"),
+			Aborted => write!(f, "aborted"),
+			OutOfMemory => write!(f, "out of memory"),
+			Io => write!(f, "I/O error"),
+			Corrupt => write!(f, "corrupted database"),
+			NotADatabase => write!(f, "not a database"),
+			DiskFull => write!(f, "disk full"),
+			CantOpen => write!(f, "unable to open database"),
+			PermissionDenied => write!(f, "permission denied"),
+			Unauthorized => write!(f, "unauthorized"),
 			SchemaChanged => write!(f, "schema changed"),
-			ConstraintViolation => write!(f, "constraint violation")
+			TooBig => write!(f, "too big"),
+			ConstraintViolation => write!(f, "constraint violation"),
+			TypeMismatch => write!(f, "type mismatch"),
+			Misuse => write!(f, "misuse"),
+			OutOfRange => write!(f, "out of range"),
+			RowNotFound => write!(f, "no row returned"),
+			TooManyRows => write!(f, "more than one row returned"),
+			MultipleStatements => write!(f, "more than one statement"),
+			ParameterCountMismatch { expected, found } => write!(f, "expected {} parameters, found {} in statement", expected, found),
+			ColumnCountMismatch { expected, found } => write!(f, "expected {} columns, found {} in statement", expected, found)
 		}
 	}
 }
 
 impl fmt::Display for Error {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		self.kind.fmt(f)
+		match &self.source {
+			Some(source) => write!(f, "{}: {}", self.kind, source),
+			None => self.kind.fmt(f)
+		}
+	}
+}
+
+/// The backend error, if any, is given as source.
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match &self.source {
+			Some(source) => Some(source.as_ref()),
+			None => None
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::de::Error for Error {
+	fn custom<T: fmt::Display>(msg: T) -> Error {
+		ErrorKind::Conversion(msg.to_string()).err()
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::ser::Error for Error {
+	fn custom<T: fmt::Display>(msg: T) -> Error {
+		ErrorKind::Conversion(msg.to_string()).err()
+	}
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for Error {
+	fn from(e: serde_json::Error) -> Error {
+		Error::new(ErrorKind::Conversion(e.to_string()), Some(Box::new(e)))
 	}
 }
@@ -0,0 +1,122 @@
+use futures::{
+	Stream,
+	StreamExt,
+	TryStreamExt
+};
+use crate::{
+	Result,
+	ErrorKind
+};
+
+/// Outcome of a statement that does not return rows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ExecResult {
+	/// Number of rows inserted, updated or deleted by the statement.
+	///
+	/// Rows modified by triggers or foreign key actions are not counted.
+	pub rows_affected: u64,
+
+	/// Row identifier of the most recent successful insertion on the connection.
+	pub last_insert_rowid: i64
+}
+
+/// Result of a statement execution.
+pub enum Execution<R> {
+	/// The statement returns rows.
+	Rows(R),
+
+	/// The statement does not return rows.
+	Done(ExecResult)
+}
+
+impl<R> Execution<R> {
+	pub fn is_rows(&self) -> bool {
+		matches!(self, Execution::Rows(_))
+	}
+
+	pub fn is_done(&self) -> bool {
+		matches!(self, Execution::Done(_))
+	}
+
+	/// Returned rows, if any.
+	pub fn rows(self) -> Option<R> {
+		match self {
+			Execution::Rows(rows) => Some(rows),
+			Execution::Done(_) => None
+		}
+	}
+
+	/// Execution result, if the statement does not return rows.
+	pub fn result(&self) -> Option<&ExecResult> {
+		match self {
+			Execution::Rows(_) => None,
+			Execution::Done(result) => Some(result)
+		}
+	}
+
+	/// Returned rows.
+	///
+	/// Panics if the statement does not return rows.
+	pub fn unwrap_rows(self) -> R {
+		match self {
+			Execution::Rows(rows) => rows,
+			Execution::Done(_) => panic!("statement does not return rows")
+		}
+	}
+
+	/// Execution result.
+	///
+	/// Panics if the statement returns rows.
+	pub fn unwrap_done(self) -> ExecResult {
+		match self {
+			Execution::Rows(_) => panic!("statement returns rows"),
+			Execution::Done(result) => result
+		}
+	}
+
+	pub fn map_rows<S, F: FnOnce(R) -> S>(self, f: F) -> Execution<S> {
+		match self {
+			Execution::Rows(rows) => Execution::Rows(f(rows)),
+			Execution::Done(result) => Execution::Done(result)
+		}
+	}
+}
+
+impl<R, S: Stream<Item = Result<R>> + Unpin> Execution<S> {
+	/// Collect the returned rows.
+	///
+	/// A statement that does not return rows gives an empty list.
+	pub async fn all(self) -> Result<Vec<R>> {
+		match self {
+			Execution::Rows(rows) => rows.try_collect().await,
+			Execution::Done(_) => Ok(Vec::new())
+		}
+	}
+
+	/// Returned row, if any.
+	///
+	/// Returns a `TooManyRows` error if the statement returns more than one row.
+	pub async fn optional(self) -> Result<Option<R>> {
+		match self {
+			Execution::Rows(mut rows) => match rows.next().await {
+				Some(row) => {
+					let row = row?;
+					match rows.next().await {
+						Some(_) => Err(ErrorKind::TooManyRows.err()),
+						None => Ok(Some(row))
+					}
+				},
+				None => Ok(None)
+			},
+			Execution::Done(_) => Ok(None)
+		}
+	}
+
+	/// Returned row.
+	///
+	/// Returns a `RowNotFound` error if the statement returns no row,
+	/// and a `TooManyRows` error if it returns more than one row.
+	pub async fn one(self) -> Result<R> {
+		self.optional().await?.ok_or_else(|| ErrorKind::RowNotFound.err())
+	}
+}
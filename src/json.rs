@@ -0,0 +1,366 @@
+//! JSON values stored as text or in the SQLite JSONB binary format.
+use std::convert::TryFrom;
+use mown::Mown;
+use serde::{
+	Serialize,
+	de::DeserializeOwned
+};
+use serde_json::{
+	Map,
+	Number
+};
+use crate::{
+	Value,
+	FromValue,
+	FromRow,
+	RowValues,
+	Result,
+	ErrorKind
+};
+
+/// Value stored as JSON text.
+///
+/// Values read from the database can also be in the JSONB format, for instance if they are
+/// stored with the `jsonb` SQL function.
+///
+/// The conversion from a column value will panic if the value is not valid JSON,
+/// or if it cannot be deserialized into `T`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Json<T>(pub T);
+
+/// Value stored in the SQLite JSONB binary format.
+///
+/// JSONB is supported by SQLite 3.45.0 or later.
+/// With older versions (see [`supports_jsonb`](crate::sqlite::supports_jsonb)), the value is stored as JSON text.
+///
+/// Values read from the database can also be JSON text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Jsonb<T>(pub T);
+
+macro_rules! json_wrapper {
+	($($ty:ident),*) => {
+		$(
+			impl<T: DeserializeOwned> FromValue for $ty<T> {
+				fn from<'a>(value: Value<'a>) -> Self {
+					let json = match value {
+						Value::Text(text) => serde_json::from_str(&text).expect("invalid JSON"),
+						Value::Blob(bytes) => decode_jsonb(&bytes).expect("invalid JSONB"),
+						_ => panic!("invalid conversion")
+					};
+
+					$ty(serde_json::from_value(json).expect("invalid conversion"))
+				}
+
+				fn accepts(value: &Value) -> bool {
+					let json = match value {
+						Value::Text(text) => serde_json::from_str(text).ok(),
+						Value::Blob(bytes) => decode_jsonb(bytes).ok(),
+						_ => None
+					};
+
+					json.map(|json| serde_json::from_value::<T>(json).is_ok()).unwrap_or(false)
+				}
+			}
+		)*
+	};
+}
+
+wrapper!(Json, Jsonb);
+json_wrapper!(Json, Jsonb);
+
+impl<T: Serialize> Json<T> {
+	/// Convert into a column value.
+	///
+	/// Returns an error if `T` cannot be serialized as JSON, for instance a map with non-string keys.
+	pub fn to_value(&self) -> Result<Value<'static>> {
+		Ok(Value::Text(Mown::Owned(serde_json::to_string(&self.0)?)))
+	}
+}
+
+impl<T: Serialize> Jsonb<T> {
+	/// Convert into a column value.
+	///
+	/// Returns an error if `T` cannot be serialized as JSON, for instance a map with non-string keys.
+	pub fn to_value(&self) -> Result<Value<'static>> {
+		if crate::sqlite::supports_jsonb() {
+			let json = serde_json::to_value(&self.0)?;
+			Ok(Value::Blob(Mown::Owned(encode_jsonb(&json))))
+		} else {
+			Json(&self.0).to_value()
+		}
+	}
+}
+
+/// Panics if the value cannot be serialized as JSON (see [`Json::to_value`]).
+impl<'a, T: Serialize> From<Json<T>> for Value<'a> {
+	fn from(json: Json<T>) -> Value<'a> {
+		json.to_value().expect("invalid JSON value")
+	}
+}
+
+/// Panics if the value cannot be serialized as JSON (see [`Jsonb::to_value`]).
+impl<'a, T: Serialize> From<Jsonb<T>> for Value<'a> {
+	fn from(json: Jsonb<T>) -> Value<'a> {
+		json.to_value().expect("invalid JSON value")
+	}
+}
+
+/// JSON text or JSONB blob.
+///
+/// Text that is not valid JSON, such as strings returned by `json_extract`, is converted into a JSON string.
+/// Numbers are converted with [`to_json`], and `NULL` into `null`.
+/// The conversion will panic if a blob is not valid JSONB.
+impl FromValue for serde_json::Value {
+	fn from<'a>(value: Value<'a>) -> Self {
+		match value {
+			Value::Text(text) => serde_json::from_str(&text).unwrap_or_else(|_| serde_json::Value::String(text.to_string())),
+			Value::Blob(bytes) => decode_jsonb(&bytes).expect("invalid JSONB"),
+			value => to_json(value)
+		}
+	}
+
+	fn accepts(value: &Value) -> bool {
+		match value {
+			Value::Blob(bytes) => decode_jsonb(bytes).is_ok(),
+			_ => true
+		}
+	}
+}
+
+/// Stored as JSON text.
+impl<'a> From<serde_json::Value> for Value<'a> {
+	fn from(json: serde_json::Value) -> Value<'a> {
+		Value::Text(Mown::Owned(json.to_string()))
+	}
+}
+
+/// Convert a column value into a JSON value, without parsing text.
+///
+/// Blobs are converted into arrays of bytes, and non-finite floats into `null`.
+pub fn to_json(value: Value) -> serde_json::Value {
+	match value {
+		Value::Integer(i) => serde_json::Value::Number(i.into()),
+		Value::Float(f) => Number::from_f64(f).map(serde_json::Value::Number).unwrap_or(serde_json::Value::Null),
+		Value::Text(text) => serde_json::Value::String(text.to_string()),
+		Value::Blob(bytes) => serde_json::Value::Array(bytes.iter().map(|b| serde_json::Value::Number((*b).into())).collect()),
+		Value::Null => serde_json::Value::Null
+	}
+}
+
+/// JSON object of the row values (converted with [`to_json`]), by column name.
+///
+/// If several columns have the same name, the first one is kept.
+impl FromRow for Map<String, serde_json::Value> {
+	fn from<'a, R: RowValues<'a>>(row: R) -> Self {
+		let names: Vec<_> = (0..row.column_count()).map(|i| row.column_name(i).unwrap_or_default().to_string()).collect();
+		let mut map = Map::new();
+		for (name, value) in names.into_iter().zip(row) {
+			map.entry(name).or_insert_with(|| to_json(value));
+		}
+
+		map
+	}
+}
+
+const NULL: u8 = 0;
+const TRUE: u8 = 1;
+const FALSE: u8 = 2;
+const INT: u8 = 3;
+const INT5: u8 = 4;
+const FLOAT: u8 = 5;
+const FLOAT5: u8 = 6;
+const TEXT: u8 = 7;
+const TEXTJ: u8 = 8;
+const TEXT5: u8 = 9;
+const TEXTRAW: u8 = 10;
+const ARRAY: u8 = 11;
+const OBJECT: u8 = 12;
+
+/// Encode a JSON value in the JSONB format.
+pub fn encode_jsonb(json: &serde_json::Value) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	encode_element(json, &mut bytes);
+	bytes
+}
+
+fn encode_element(json: &serde_json::Value, bytes: &mut Vec<u8>) {
+	match json {
+		serde_json::Value::Null => bytes.push(NULL),
+		serde_json::Value::Bool(true) => bytes.push(TRUE),
+		serde_json::Value::Bool(false) => bytes.push(FALSE),
+		serde_json::Value::Number(n) => {
+			let ty = if n.is_f64() { FLOAT } else { INT };
+			encode_header(ty, n.to_string().as_bytes(), bytes)
+		},
+		serde_json::Value::String(str) => {
+			let ty = if str.chars().any(|c| c == '"' || c == '\\' || c.is_control()) { TEXTRAW } else { TEXT };
+			encode_header(ty, str.as_bytes(), bytes)
+		},
+		serde_json::Value::Array(array) => {
+			let mut payload = Vec::new();
+			for item in array {
+				encode_element(item, &mut payload)
+			}
+			encode_header(ARRAY, &payload, bytes)
+		},
+		serde_json::Value::Object(object) => {
+			let mut payload = Vec::new();
+			for (key, value) in object {
+				encode_element(&serde_json::Value::String(key.clone()), &mut payload);
+				encode_element(value, &mut payload)
+			}
+			encode_header(OBJECT, &payload, bytes)
+		}
+	}
+}
+
+/// Write an element header followed by its payload.
+fn encode_header(ty: u8, payload: &[u8], bytes: &mut Vec<u8>) {
+	let len = payload.len();
+	if len <= 11 {
+		bytes.push((len as u8) << 4 | ty)
+	} else if len <= 0xff {
+		bytes.push(0xc0 | ty);
+		bytes.push(len as u8)
+	} else if len <= 0xffff {
+		bytes.push(0xd0 | ty);
+		bytes.extend_from_slice(&(len as u16).to_be_bytes())
+	} else if len <= 0xffff_ffff {
+		bytes.push(0xe0 | ty);
+		bytes.extend_from_slice(&(len as u32).to_be_bytes())
+	} else {
+		bytes.push(0xf0 | ty);
+		bytes.extend_from_slice(&(len as u64).to_be_bytes())
+	}
+
+	bytes.extend_from_slice(payload)
+}
+
+fn invalid_jsonb() -> crate::Error {
+	ErrorKind::Conversion("invalid JSONB".to_string()).err()
+}
+
+/// Maximum nesting depth of decoded arrays and objects.
+const MAX_DEPTH: usize = 128;
+
+/// Decode a JSON value in the JSONB format.
+pub fn decode_jsonb(bytes: &[u8]) -> Result<serde_json::Value> {
+	let (json, len) = decode_element(bytes, 0)?;
+	if len == bytes.len() {
+		Ok(json)
+	} else {
+		Err(invalid_jsonb())
+	}
+}
+
+/// Decode the element at the start of the given bytes.
+///
+/// Returns the element and its total length.
+/// Arrays and objects nested deeper than [`MAX_DEPTH`] are rejected.
+fn decode_element(bytes: &[u8], depth: usize) -> Result<(serde_json::Value, usize)> {
+	let header = *bytes.first().ok_or_else(invalid_jsonb)?;
+	let (size_len, payload_len) = match header >> 4 {
+		len @ 0..=11 => (0, len as u64),
+		n => {
+			let size_len = 1 << (n - 12);
+			let size = bytes.get(1..1 + size_len).ok_or_else(invalid_jsonb)?;
+			(size_len, size.iter().fold(0u64, |len, b| len << 8 | *b as u64))
+		}
+	};
+
+	let start = 1 + size_len;
+	let end = usize::try_from(payload_len).ok().and_then(|len| start.checked_add(len)).ok_or_else(invalid_jsonb)?;
+	let payload = bytes.get(start..end).ok_or_else(invalid_jsonb)?;
+	let text = || std::str::from_utf8(payload).map_err(|_| invalid_jsonb());
+
+	let json = match header & 0x0f {
+		NULL => serde_json::Value::Null,
+		TRUE => serde_json::Value::Bool(true),
+		FALSE => serde_json::Value::Bool(false),
+		INT | FLOAT => serde_json::from_str(text()?)?,
+		INT5 => {
+			let text = text()?;
+			let (negative, digits) = match text.as_bytes().first() {
+				Some(b'-') => (true, &text[1..]),
+				Some(b'+') => (false, &text[1..]),
+				_ => (false, text)
+			};
+
+			let digits = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")).ok_or_else(invalid_jsonb)?;
+			let n = i64::from_str_radix(digits, 16).map_err(|_| invalid_jsonb())?;
+			serde_json::Value::Number(Number::from(if negative { -n } else { n }))
+		},
+		FLOAT5 => {
+			let f: f64 = text()?.parse().map_err(|_| invalid_jsonb())?;
+			// Infinite and NaN values cannot be represented in JSON.
+			Number::from_f64(f).map(serde_json::Value::Number).unwrap_or(serde_json::Value::Null)
+		},
+		TEXT | TEXTRAW => serde_json::Value::String(text()?.to_string()),
+		TEXTJ => serde_json::Value::String(serde_json::from_str(&format!("\"{}\"", text()?))?),
+		TEXT5 => serde_json::Value::String(serde_json::from_str(&format!("\"{}\"", json5_escapes(text()?)))?),
+		ARRAY | OBJECT if depth >= MAX_DEPTH => return Err(invalid_jsonb()),
+		ARRAY => {
+			let mut array = Vec::new();
+			let mut offset = 0;
+			while offset < payload.len() {
+				let (item, len) = decode_element(&payload[offset..], depth + 1)?;
+				array.push(item);
+				offset += len
+			}
+			serde_json::Value::Array(array)
+		},
+		OBJECT => {
+			let mut object = Map::new();
+			let mut offset = 0;
+			while offset < payload.len() {
+				let (key, len) = decode_element(&payload[offset..], depth + 1)?;
+				offset += len;
+				let (value, len) = decode_element(&payload[offset..], depth + 1)?;
+				offset += len;
+				match key {
+					serde_json::Value::String(key) => object.insert(key, value),
+					_ => return Err(invalid_jsonb())
+				};
+			}
+			serde_json::Value::Object(object)
+		},
+		_ => return Err(invalid_jsonb())
+	};
+
+	Ok((json, end))
+}
+
+/// Translate JSON5 string escapes into JSON string escapes.
+fn json5_escapes(text: &str) -> String {
+	let mut result = String::with_capacity(text.len());
+	let mut chars = text.chars();
+	while let Some(c) = chars.next() {
+		if c == '\\' {
+			match chars.next() {
+				Some('\'') => result.push('\''),
+				Some('v') => result.push_str("\\u000b"),
+				Some('0') => result.push_str("\\u0000"),
+				Some('x') => {
+					result.push_str("\\u00");
+					result.extend(chars.by_ref().take(2))
+				},
+				// Line continuation.
+				Some('\n') | Some('\u{2028}') | Some('\u{2029}') => (),
+				Some('\r') => {
+					if chars.clone().next() == Some('\n') {
+						chars.next();
+					}
+				},
+				Some(c) => {
+					result.push('\\');
+					result.push(c)
+				},
+				None => result.push('\\')
+			}
+		} else {
+			result.push(c)
+		}
+	}
+
+	result
+}
@@ -5,25 +5,74 @@ extern crate pin_utils;
 
 use futures::{
 	FutureExt,
+	StreamExt,
 	future::{
 		LocalBoxFuture
 	}
 };
 
+/// Implement `into_inner`, `Deref` and `DerefMut` for the given single field wrappers.
+macro_rules! wrapper {
+	($($ty:ident),*) => {
+		$(
+			impl<T> $ty<T> {
+				pub fn into_inner(self) -> T {
+					self.0
+				}
+			}
+
+			impl<T> std::ops::Deref for $ty<T> {
+				type Target = T;
+
+				fn deref(&self) -> &T {
+					&self.0
+				}
+			}
+
+			impl<T> std::ops::DerefMut for $ty<T> {
+				fn deref_mut(&mut self) -> &mut T {
+					&mut self.0
+				}
+			}
+		)*
+	};
+}
+
 mod error;
 mod backoff;
 mod backend;
 mod value;
 mod row;
+mod execution;
 mod parsing;
 mod transaction;
+mod query;
+
+#[cfg(feature = "serde")]
+pub mod de;
+#[cfg(feature = "serde")]
+pub mod ser;
+#[cfg(feature = "json")]
+pub mod json;
 
 pub use error::*;
 pub use self::backoff::*;
 pub use backend::*;
 pub use value::*;
 pub use row::*;
+pub use execution::*;
 pub use transaction::*;
+pub use query::*;
+
+#[cfg(feature = "serde")]
+pub use de::Deserialized;
+#[cfg(feature = "macros")]
+pub use sql_connect_macros::query;
+#[cfg(feature = "json")]
+pub use json::{
+	Json,
+	Jsonb
+};
 
 pub trait Connection: Sized {
 	type Statement;
@@ -34,6 +83,17 @@ pub trait Connection: Sized {
 	/// with no terminating semicolon (`;`).
 	fn prepare(&mut self, sql: &str) -> Result<Option<Self::Statement>>;
 
+	/// Compile an SQL statement into a typed query.
+	///
+	/// Fails if the statement is empty, or if it does not match the parameters or row type
+	/// (see [`Query::new`]).
+	fn prepare_query<'p, P: Params<'p>, R: FromRow>(&mut self, sql: &str) -> Result<Query<P, R, Self::Statement>> where Self::Statement: StatementInfo {
+		match self.prepare(sql)? {
+			Some(statement) => Query::new(statement),
+			None => Err(ErrorKind::InvalidQuery.err())
+		}
+	}
+
 	/// Compile a list of SQL statements.
 	///
 	/// Statements must be separated by a (`;`) semicolon.
@@ -55,30 +115,31 @@ pub trait Connection: Sized {
 	///
 	/// Every pending statements will be executed before the given statement using the
 	/// [`execute_pending_statements`] function.
-	fn execute<'a, R: 'a + FromRow>(&'a mut self, statement: &'a Self::Statement, args: Vec<Value>) -> LocalBoxFuture<'a, Result<Option<Rows<'a, R>>>>;
+	fn execute<'a, R: 'a + FromRow>(&'a mut self, statement: &'a Self::Statement, args: Vec<Value>) -> LocalBoxFuture<'a, Result<Execution<Rows<'a, R>>>>;
 
 	/// Execute the statement by consuming it.
-	fn consume<'a, R: 'a + FromRow>(&'a mut self, statement: Self::Statement, args: Vec<Value>) -> LocalBoxFuture<'a, Result<Option<OwnedRows<'a, Self::Statement, R>>>> where Self::Statement: 'a {
+	fn consume<'a, R: 'a + FromRow>(&'a mut self, statement: Self::Statement, args: Vec<Value>) -> LocalBoxFuture<'a, Result<Execution<OwnedRows<'a, Self::Statement, R>>>> where Self::Statement: 'a {
+		// The statement is boxed so that it does not move while the rows refer to it.
+		let statement = Box::new(statement);
 		unsafe {
 			// This is safe because the statement will be embeded in the `OwnedRows` so that it won't be dropped before the rows.
-			let exec: LocalBoxFuture<'a, Result<Option<Rows<'a, R>>>> = std::mem::transmute(self.execute::<R>(&statement, args));
+			let exec: LocalBoxFuture<'a, Result<Execution<Rows<'a, R>>>> = std::mem::transmute(self.execute::<R>(&statement, args));
 			async move {
-				match exec.await? {
-					Some(rows) => Ok(Some(rows.into_owned(statement))),
-					None => Ok(None)
-				}
+				Ok(exec.await?.map_rows(|rows| rows.into_owned(statement)))
 			}.boxed_local()
 		}
 	}
 
 	/// Prepare and execute a statement.
-	fn execute_sql<'a, R: 'a + FromRow>(&'a mut self, sql: &str, args: Vec<Value>) -> LocalBoxFuture<'a, Result<Option<OwnedRows<'a, Self::Statement, R>>>> where Self::Statement: 'a {
+	///
+	/// An empty statement is considered as a statement affecting no rows.
+	fn execute_sql<'a, R: 'a + FromRow>(&'a mut self, sql: &str, args: Vec<Value>) -> LocalBoxFuture<'a, Result<Execution<OwnedRows<'a, Self::Statement, R>>>> where Self::Statement: 'a {
 		match self.prepare(sql) {
 			Ok(Some(statement)) => {
 				self.consume(statement, args)
 			},
 			Ok(None) => async move {
-				Ok(None)
+				Ok(Execution::Done(ExecResult::default()))
 			}.boxed_local(),
 			Err(e) => async move {
 				Err(e)
@@ -86,6 +147,62 @@ pub trait Connection: Sized {
 		}
 	}
 
+	/// Prepare and execute a statement, and collect its rows.
+	///
+	/// A statement that does not return rows gives an empty list.
+	fn fetch_all<'a, R: 'a + FromRow>(&'a mut self, sql: &str, args: Vec<Value>) -> LocalBoxFuture<'a, Result<Vec<R>>> where Self::Statement: 'a {
+		let execution = self.execute_sql::<R>(sql, args);
+		async move {
+			execution.await?.all().await
+		}.boxed_local()
+	}
+
+	/// Prepare and execute a statement returning at most one row.
+	///
+	/// Returns a `TooManyRows` error if the statement returns more than one row.
+	fn fetch_optional<'a, R: 'a + FromRow>(&'a mut self, sql: &str, args: Vec<Value>) -> LocalBoxFuture<'a, Result<Option<R>>> where Self::Statement: 'a {
+		let execution = self.execute_sql::<R>(sql, args);
+		async move {
+			execution.await?.optional().await
+		}.boxed_local()
+	}
+
+	/// Prepare and execute a statement returning exactly one row.
+	///
+	/// Returns a `RowNotFound` error if the statement returns no row,
+	/// and a `TooManyRows` error if it returns more than one row.
+	fn fetch_one<'a, R: 'a + FromRow>(&'a mut self, sql: &str, args: Vec<Value>) -> LocalBoxFuture<'a, Result<R>> where Self::Statement: 'a {
+		let execution = self.execute_sql::<R>(sql, args);
+		async move {
+			execution.await?.one().await
+		}.boxed_local()
+	}
+
+	/// Prepare and execute a statement returning exactly one row of one column,
+	/// and return the value of this column.
+	///
+	/// Fails like [`Connection::fetch_one`].
+	fn fetch_scalar<'a, T: 'a + FromValue>(&'a mut self, sql: &str, args: Vec<Value>) -> LocalBoxFuture<'a, Result<T>> where Self::Statement: 'a {
+		self.fetch_one::<T>(sql, args)
+	}
+
+	/// Prepare and execute a statement, and map each of its rows with the given function.
+	///
+	/// Rows are mapped as they are fetched, and the first error returned by `f` is returned.
+	fn query_map<'a, R: 'a + FromRow, T: 'a, F: 'a + FnMut(R) -> Result<T>>(&'a mut self, sql: &str, args: Vec<Value>, mut f: F) -> LocalBoxFuture<'a, Result<Vec<T>>> where Self::Statement: 'a {
+		let execution = self.execute_sql::<R>(sql, args);
+		async move {
+			let mut list = Vec::new();
+			if let Execution::Rows(mut rows) = execution.await? {
+				while let Some(row) = rows.next().await {
+					list.push(f(row?)?)
+				}
+			}
+
+			Ok(list)
+		}.boxed_local()
+	}
+
 	/// Prepare and execute a statement.
 	fn execute_script<'a>(&'a mut self, sql: &'a str) -> LocalBoxFuture<'a, Result<()>> where Self::Statement: 'a {
 		async move {
@@ -1,74 +1,159 @@
 use std::iter::Peekable;
 use std::str::CharIndices;
 
+/// Iterator over the statements of an SQL statement list.
+///
+/// Statements are separated by semicolons (`;`), ignoring semicolons appearing in
+/// strings, quoted identifiers (`"..."`, `[...]` and `` `...` ``), comments (`-- ...` and `/* ... */`),
+/// parentheses, and in the `BEGIN ... END` body of `CREATE TRIGGER` statements.
+///
+/// Statements consisting only of whitespace are skipped.
 pub struct Statements<'a> {
 	sql: &'a str,
 	chars: Peekable<CharIndices<'a>>,
-	offset: usize,
-	state: Vec<State>
+	offset: usize
 }
 
-#[derive(Clone, Copy)]
-enum State {
-	Group,
-	String
+/// Beginning of a `CREATE TRIGGER` statement.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Prefix {
+	/// No keyword read yet.
+	Start,
+
+	/// `CREATE` keyword read.
+	Create,
+
+	/// `CREATE TEMP` or `CREATE TEMPORARY` keywords read.
+	CreateTemp,
+
+	/// `CREATE [TEMP] TRIGGER` keywords read.
+	Trigger,
+
+	/// Any other statement.
+	Other
 }
 
-impl<'a> Iterator for Statements<'a> {
-	type Item = &'a str;
+impl<'a> Statements<'a> {
+	/// Skip characters until the given closing character (included).
+	///
+	/// If `doubled` is true, the closing character is escaped by doubling it.
+	fn skip_quoted(&mut self, close: char, doubled: bool) {
+		while let Some((_, c)) = self.chars.next() {
+			if c == close {
+				match self.chars.peek() {
+					Some((_, next)) if doubled && *next == close => {
+						self.chars.next(); // skip the escaped quote.
+					},
+					_ => break
+				}
+			}
+		}
+	}
+
+	/// Skip a `-- ...` comment, up to the end of the line.
+	fn skip_line_comment(&mut self) {
+		for (_, c) in self.chars.by_ref() {
+			if c == '\n' {
+				break
+			}
+		}
+	}
+
+	/// Skip a `/* ... */` comment.
+	fn skip_block_comment(&mut self) {
+		self.chars.next(); // skip the `*`.
+		while let Some((_, c)) = self.chars.next() {
+			if c == '*' {
+				if let Some((_, '/')) = self.chars.peek() {
+					self.chars.next();
+					break
+				}
+			}
+		}
+	}
+
+	/// Read the word starting at the given byte index.
+	fn word(&mut self, start: usize) -> &'a str {
+		let mut end = self.sql.len();
+		while let Some((i, c)) = self.chars.peek() {
+			if is_word_char(*c) {
+				self.chars.next();
+			} else {
+				end = *i;
+				break
+			}
+		}
+
+		&self.sql[start..end]
+	}
+
+	/// Find the end of the next statement.
+	///
+	/// Returns the statement and the byte index following it.
+	fn statement(&mut self) -> Option<(&'a str, usize)> {
+		if self.offset >= self.sql.len() {
+			return None
+		}
+
+		let mut depth = 0usize;
+		let mut prefix = Prefix::Start;
+		let mut block = 0usize;
 
-	fn next(&mut self) -> Option<&'a str> {
 		while let Some((i, c)) = self.chars.next() {
-			match self.state.last() {
-				None => match c {
-					';' => {
-						let stmt = unsafe { self.sql.get_unchecked(self.offset..i) };
-						self.offset = i + 1;
-						return Some(stmt)
-					},
-					'\'' => {
-						self.state.push(State::String)
-					},
-					'(' => {
-						self.state.push(State::Group)
-					},
-					_ => ()
+			match c {
+				';' if depth == 0 && block == 0 => {
+					return Some((&self.sql[self.offset..i], i + 1))
 				},
-				Some(State::Group) => match c {
-					')' => {
-						self.state.pop();
-					},
-					'(' => {
-						self.state.push(State::Group)
-					},
-					'\'' => {
-						self.state.push(State::String)
-					},
-					_ => ()
-				},
-				Some(State::String) => match c {
-					'\'' => {
-						match self.chars.peek() {
-							Some((_, '\'')) => {
-								self.chars.next(); // skip the next quote.
-							},
-							_ => {
-								self.state.pop();
-							}
+				'\'' => self.skip_quoted('\'', true),
+				'"' => self.skip_quoted('"', true),
+				'`' => self.skip_quoted('`', true),
+				'[' => self.skip_quoted(']', false),
+				'-' if matches!(self.chars.peek(), Some((_, '-'))) => self.skip_line_comment(),
+				'/' if matches!(self.chars.peek(), Some((_, '*'))) => self.skip_block_comment(),
+				'(' => depth += 1,
+				')' => depth = depth.saturating_sub(1),
+				c if is_word_char(c) => {
+					let word = self.word(i);
+					prefix = match prefix {
+						Prefix::Start if word.eq_ignore_ascii_case("CREATE") => Prefix::Create,
+						Prefix::Create if word.eq_ignore_ascii_case("TEMP") || word.eq_ignore_ascii_case("TEMPORARY") => Prefix::CreateTemp,
+						Prefix::Create | Prefix::CreateTemp if word.eq_ignore_ascii_case("TRIGGER") => Prefix::Trigger,
+						Prefix::Trigger => Prefix::Trigger,
+						_ => Prefix::Other
+					};
+
+					if prefix == Prefix::Trigger {
+						if word.eq_ignore_ascii_case("BEGIN") || (block > 0 && word.eq_ignore_ascii_case("CASE")) {
+							block += 1
+						} else if block > 0 && word.eq_ignore_ascii_case("END") {
+							block -= 1
 						}
-					},
-					_ => ()
-				}
+					}
+				},
+				_ => ()
 			}
 		}
 
-		if self.offset < self.sql.len() {
-			let stmt = unsafe { self.sql.get_unchecked(self.offset..self.sql.len()) };
-			self.offset = self.sql.len();
-			Some(stmt)
-		} else {
-			None
+		Some((&self.sql[self.offset..], self.sql.len()))
+	}
+}
+
+fn is_word_char(c: char) -> bool {
+	c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+impl<'a> Iterator for Statements<'a> {
+	type Item = &'a str;
+
+	fn next(&mut self) -> Option<&'a str> {
+		while let Some((stmt, end)) = self.statement() {
+			self.offset = end;
+			if !stmt.trim().is_empty() {
+				return Some(stmt)
+			}
 		}
+
+		None
 	}
 }
 
@@ -76,8 +161,7 @@ pub fn split_statement_list(sql: &str) -> Statements {
 	Statements {
 		sql,
 		chars: sql.char_indices().peekable(),
-		offset: 0,
-		state: Vec::new()
+		offset: 0
 	}
 }
 
@@ -103,7 +187,14 @@ mod tests {
 	fn single_with_semicolon_and_space() {
 		let mut statements = split_statement_list("A;  ");
 		assert_eq!(statements.next(), Some("A"));
-		assert_eq!(statements.next(), Some("  "));
+		assert_eq!(statements.next(), None);
+	}
+
+	#[test]
+	fn empty_statements() {
+		let mut statements = split_statement_list(" ; A;;\n\t; B ;  ");
+		assert_eq!(statements.next(), Some(" A"));
+		assert_eq!(statements.next(), Some(" B "));
 		assert_eq!(statements.next(), None);
 	}
 
@@ -133,4 +224,66 @@ mod tests {
 		assert_eq!(statements.next(), Some(" C"));
 		assert_eq!(statements.next(), None);
 	}
+
+	#[test]
+	fn with_line_comment() {
+		let mut statements = split_statement_list("A -- foo; bar\nB; C -- baz;");
+		assert_eq!(statements.next(), Some("A -- foo; bar\nB"));
+		assert_eq!(statements.next(), Some(" C -- baz;"));
+		assert_eq!(statements.next(), None);
+	}
+
+	#[test]
+	fn with_block_comment() {
+		let mut statements = split_statement_list("A /* foo; * bar; */ B; C /*/ ; */; D - E / F");
+		assert_eq!(statements.next(), Some("A /* foo; * bar; */ B"));
+		assert_eq!(statements.next(), Some(" C /*/ ; */"));
+		assert_eq!(statements.next(), Some(" D - E / F"));
+		assert_eq!(statements.next(), None);
+	}
+
+	#[test]
+	fn with_quoted_identifiers() {
+		let mut statements = split_statement_list("A \"foo;\"\"bar\" B; C [foo;bar] D; E `foo;``bar` F; G");
+		assert_eq!(statements.next(), Some("A \"foo;\"\"bar\" B"));
+		assert_eq!(statements.next(), Some(" C [foo;bar] D"));
+		assert_eq!(statements.next(), Some(" E `foo;``bar` F"));
+		assert_eq!(statements.next(), Some(" G"));
+		assert_eq!(statements.next(), None);
+	}
+
+	#[test]
+	fn with_string_in_group() {
+		let mut statements = split_statement_list("A ('(;') B; C");
+		assert_eq!(statements.next(), Some("A ('(;') B"));
+		assert_eq!(statements.next(), Some(" C"));
+		assert_eq!(statements.next(), None);
+	}
+
+	#[test]
+	fn with_trigger() {
+		let sql = "CREATE TRIGGER t AFTER INSERT ON a BEGIN INSERT INTO b VALUES (1); UPDATE c SET x = CASE WHEN y THEN 1 ELSE 2 END; END; SELECT 1";
+		let mut statements = split_statement_list(sql);
+		assert_eq!(statements.next(), Some("CREATE TRIGGER t AFTER INSERT ON a BEGIN INSERT INTO b VALUES (1); UPDATE c SET x = CASE WHEN y THEN 1 ELSE 2 END; END"));
+		assert_eq!(statements.next(), Some(" SELECT 1"));
+		assert_eq!(statements.next(), None);
+	}
+
+	#[test]
+	fn with_temporary_trigger() {
+		let mut statements = split_statement_list("create temp trigger t before delete on a when (select case when 1 then 1 end) begin delete from b; end;begin;end");
+		assert_eq!(statements.next(), Some("create temp trigger t before delete on a when (select case when 1 then 1 end) begin delete from b; end"));
+		assert_eq!(statements.next(), Some("begin"));
+		assert_eq!(statements.next(), Some("end"));
+		assert_eq!(statements.next(), None);
+	}
+
+	#[test]
+	fn not_a_trigger() {
+		let mut statements = split_statement_list("CREATE TABLE trigger_log (x); BEGIN; END");
+		assert_eq!(statements.next(), Some("CREATE TABLE trigger_log (x)"));
+		assert_eq!(statements.next(), Some(" BEGIN"));
+		assert_eq!(statements.next(), Some(" END"));
+		assert_eq!(statements.next(), None);
+	}
 }
@@ -0,0 +1,137 @@
+use std::marker::PhantomData;
+use futures::future::LocalBoxFuture;
+use crate::{
+	Connection,
+	Result,
+	ErrorKind,
+	FromRow,
+	Value,
+	Rows,
+	Execution
+};
+
+/// Prepared statement information required to check typed queries.
+pub trait StatementInfo {
+	/// Number of parameters of the statement.
+	fn parameter_count(&self) -> usize;
+
+	/// Number of columns returned by the statement.
+	fn column_count(&self) -> usize;
+}
+
+/// Statically sized list of statement parameters.
+///
+/// Implemented for tuples (up to 8 elements) of values convertible into [`Value`],
+/// and for arrays of values.
+pub trait Params<'a> {
+	/// Number of parameters.
+	const COUNT: usize;
+
+	fn into_values(self) -> Vec<Value<'a>>;
+}
+
+impl<'a> Params<'a> for () {
+	const COUNT: usize = 0;
+
+	fn into_values(self) -> Vec<Value<'a>> {
+		Vec::new()
+	}
+}
+
+impl<'a, const N: usize> Params<'a> for [Value<'a>; N] {
+	const COUNT: usize = N;
+
+	fn into_values(self) -> Vec<Value<'a>> {
+		self.into()
+	}
+}
+
+macro_rules! tuple_params {
+	( $( $t:ident ),+ ) => {
+		impl<'a, $( $t: Into<Value<'a>>, )*> Params<'a> for ( $( $t, )* ) {
+			const COUNT: usize = [$( stringify!($t) ),*].len();
+
+			#[allow(non_snake_case)]
+			fn into_values(self) -> Vec<Value<'a>> {
+				let ( $( $t, )* ) = self;
+				vec![$( $t.into() ),*]
+			}
+		}
+	};
+}
+
+tuple_params!(T1);
+tuple_params!(T1, T2);
+tuple_params!(T1, T2, T3);
+tuple_params!(T1, T2, T3, T4);
+tuple_params!(T1, T2, T3, T4, T5);
+tuple_params!(T1, T2, T3, T4, T5, T6);
+tuple_params!(T1, T2, T3, T4, T5, T6, T7);
+tuple_params!(T1, T2, T3, T4, T5, T6, T7, T8);
+
+/// Prepared statement taking parameters of type `P` and returning rows of type `R`.
+///
+/// The parameter and column counts are checked when the query is created.
+/// The column count is only checked if `R` expects a fixed number of columns (see [`FromRow::column_count`]).
+/// Statements that do not return rows, such as `INSERT`, have no columns: use `()` as row type.
+pub struct Query<P, R, S> {
+	statement: S,
+	types: PhantomData<fn(P) -> R>
+}
+
+impl<P, R: FromRow, S: StatementInfo> Query<P, R, S> {
+	/// Wrap the given statement.
+	///
+	/// Returns a `ParameterCountMismatch` or `ColumnCountMismatch` error if the statement
+	/// does not match the parameters or row type.
+	pub fn new<'p>(statement: S) -> Result<Self> where P: Params<'p> {
+		let found = statement.parameter_count();
+		if found != P::COUNT {
+			return Err(ErrorKind::ParameterCountMismatch { expected: P::COUNT, found }.err())
+		}
+
+		let found = statement.column_count();
+		if let Some(expected) = R::column_count() {
+			if found != expected {
+				return Err(ErrorKind::ColumnCountMismatch { expected, found }.err())
+			}
+		}
+
+		Ok(Query {
+			statement,
+			types: PhantomData
+		})
+	}
+}
+
+impl<P, R, S> Query<P, R, S> {
+	pub fn statement(&self) -> &S {
+		&self.statement
+	}
+
+	pub fn into_statement(self) -> S {
+		self.statement
+	}
+
+	/// Execute the query through the given connection.
+	///
+	/// The query must have been prepared by this connection.
+	pub fn execute<'a, 'p, C: Connection<Statement = S>>(&'a self, connection: &'a mut C, params: P) -> LocalBoxFuture<'a, Result<Execution<Rows<'a, R>>>> where P: Params<'p>, R: 'a + FromRow {
+		connection.execute(&self.statement, params.into_values())
+	}
+
+	/// Execute the query and collect its rows.
+	pub async fn fetch_all<'a, 'p, C: Connection<Statement = S>>(&'a self, connection: &'a mut C, params: P) -> Result<Vec<R>> where P: Params<'p>, R: 'a + FromRow {
+		self.execute(connection, params).await?.all().await
+	}
+
+	/// Execute the query, expecting at most one row.
+	pub async fn fetch_optional<'a, 'p, C: Connection<Statement = S>>(&'a self, connection: &'a mut C, params: P) -> Result<Option<R>> where P: Params<'p>, R: 'a + FromRow {
+		self.execute(connection, params).await?.optional().await
+	}
+
+	/// Execute the query, expecting exactly one row.
+	pub async fn fetch_one<'a, 'p, C: Connection<Statement = S>>(&'a self, connection: &'a mut C, params: P) -> Result<R> where P: Params<'p>, R: 'a + FromRow {
+		self.execute(connection, params).await?.one().await
+	}
+}
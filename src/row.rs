@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::pin::Pin;
 use std::task::{
 	Poll,
@@ -8,22 +9,75 @@ use futures::{
 };
 use crate::{
 	Value,
+	OwnedValue,
 	FromValue,
-	Result
+	Result,
+	ErrorKind
 };
 
+/// Values of a row, in column order.
+pub trait RowValues<'a>: Iterator<Item = Value<'a>> {
+	/// Number of columns of the row.
+	fn column_count(&self) -> usize;
+
+	/// Name of the column at the given index, starting from `0`.
+	fn column_name(&self, index: usize) -> Option<&str>;
+}
+
 /// Types that can be converted from a data column.
 pub trait FromRow: Sized {
-	fn from<'a, R: Iterator<Item = Value<'a>>>(row: R) -> Self;
+	/// Convert the given row.
+	fn from<'a, R: RowValues<'a>>(row: R) -> Self;
+
+	/// Convert the given row, returning an error if it is invalid.
+	///
+	/// This is used by row streams. By default, it calls [`FromRow::from`].
+	fn try_from_row<'a, R: RowValues<'a>>(row: R) -> Result<Self> {
+		Ok(Self::from(row))
+	}
+
+	/// Number of columns expected in the row, if it is fixed.
+	fn column_count() -> Option<usize> {
+		None
+	}
+}
+
+/// Ignore the row, for statements that do not return rows.
+impl FromRow for () {
+	fn from<'a, R: RowValues<'a>>(_row: R) -> Self { }
+
+	fn column_count() -> Option<usize> {
+		Some(0)
+	}
+}
+
+/// Convert the next value of the row into the given type.
+///
+/// Returns a `TypeMismatch` error if the value cannot be converted (see [`FromValue::accepts`]),
+/// or a `ColumnCountMismatch` error if the row has less than `expected` columns.
+fn try_next<'a, T: FromValue, R: RowValues<'a>>(row: &mut R, expected: usize) -> Result<T> {
+	match row.next() {
+		Some(value) if T::accepts(&value) => Ok(T::from(value)),
+		Some(_) => Err(ErrorKind::TypeMismatch.err()),
+		None => Err(ErrorKind::ColumnCountMismatch { expected, found: row.column_count() }.err())
+	}
 }
 
 /// Convert a single-column row into the given type.
 ///
 /// The convertion will panic if the row is empty, or if the convertion from column value panics.
 impl<T> FromRow for T where T: FromValue {
-	fn from<'a, R: Iterator<Item = Value<'a>>>(mut row: R) -> T {
+	fn from<'a, R: RowValues<'a>>(mut row: R) -> T {
 		T::from(row.next().unwrap())
 	}
+
+	fn try_from_row<'a, R: RowValues<'a>>(mut row: R) -> Result<T> {
+		try_next(&mut row, 1)
+	}
+
+	fn column_count() -> Option<usize> {
+		Some(1)
+	}
 }
 
 macro_rules! tuple_from_row {
@@ -33,9 +87,18 @@ macro_rules! tuple_from_row {
 		/// The convertion will panic if the row is too short,
 		/// or if the convertion from column value panics.
 		impl < $( $t, )* > FromRow for ( $( $t ),* ) where $( $t: FromValue, )+ {
-			fn from<'a, R: Iterator<Item = Value<'a>>>(mut row: R) -> ( $( $t ),* ) {
+			fn from<'a, R: RowValues<'a>>(mut row: R) -> ( $( $t ),* ) {
 				($( $t::from(row.next().unwrap()), )*)
 			}
+
+			fn try_from_row<'a, R: RowValues<'a>>(mut row: R) -> Result<( $( $t ),* )> {
+				let expected = [$( stringify!($t) ),*].len();
+				Ok(($( try_next::<$t, _>(&mut row, expected)?, )*))
+			}
+
+			fn column_count() -> Option<usize> {
+				Some([$( stringify!($t) ),*].len())
+			}
 		}
 	};
 }
@@ -48,6 +111,170 @@ tuple_from_row!(T1, T2, T3, T4, T5, T6);
 tuple_from_row!(T1, T2, T3, T4, T5, T6, T7);
 tuple_from_row!(T1, T2, T3, T4, T5, T6, T7, T8);
 
+/// Row read without a predefined type, giving access to its columns by index or name.
+#[derive(Clone, Debug)]
+pub struct Row {
+	names: Vec<String>,
+	values: Vec<OwnedValue>
+}
+
+impl Row {
+	/// Number of columns of the row.
+	pub fn column_count(&self) -> usize {
+		self.values.len()
+	}
+
+	/// Name of the column at the given index, starting from `0`.
+	pub fn column_name(&self, index: usize) -> Option<&str> {
+		self.names.get(index).map(String::as_str)
+	}
+
+	/// Index of the first column with the given name.
+	pub fn column_index(&self, name: &str) -> Option<usize> {
+		self.names.iter().position(|n| n == name)
+	}
+
+	/// Value of the given column, if any.
+	pub fn value<I: ColumnIndex>(&self, index: I) -> Option<Value<'_>> {
+		index.index(self).map(|i| self.values[i].borrow())
+	}
+
+	/// Values of the row, in column order.
+	pub fn values(&self) -> &[OwnedValue] {
+		&self.values
+	}
+
+	pub fn into_values(self) -> Vec<OwnedValue> {
+		self.values
+	}
+
+	/// Get the value of the given column, by index or name.
+	///
+	/// Panics if there is no such column, or if the column value cannot be converted into `T`.
+	pub fn get<T: FromValue, I: ColumnIndex>(&self, index: I) -> T {
+		match self.try_get(index) {
+			Ok(t) => t,
+			Err(e) => panic!("{}", e)
+		}
+	}
+
+	/// Get the value of the given column, by index or name.
+	///
+	/// Returns an `InvalidArgument` error if there is no such column,
+	/// or a `TypeMismatch` error if the column value cannot be converted into `T`
+	/// (see [`FromValue::accepts`]).
+	pub fn try_get<T: FromValue, I: ColumnIndex>(&self, index: I) -> Result<T> {
+		match self.value(&index) {
+			Some(value) if T::accepts(&value) => Ok(T::from(value)),
+			Some(_) => Err(ErrorKind::TypeMismatch.err()),
+			None => Err(ErrorKind::InvalidArgument(format!("no column {}", index.describe())).err())
+		}
+	}
+}
+
+impl FromRow for Row {
+	fn from<'a, R: RowValues<'a>>(row: R) -> Row {
+		Row {
+			names: column_names(&row),
+			values: row.map(Value::into_owned).collect()
+		}
+	}
+}
+
+/// Names of the columns of the given row.
+fn column_names<'a, R: RowValues<'a>>(row: &R) -> Vec<String> {
+	(0..row.column_count()).map(|i| row.column_name(i).unwrap_or_default().to_string()).collect()
+}
+
+/// Values of the row, in column order.
+impl FromRow for Vec<OwnedValue> {
+	fn from<'a, R: RowValues<'a>>(row: R) -> Self {
+		row.map(Value::into_owned).collect()
+	}
+}
+
+/// Values of the row, by column name.
+///
+/// If several columns have the same name, the first one is kept.
+impl FromRow for BTreeMap<String, OwnedValue> {
+	fn from<'a, R: RowValues<'a>>(row: R) -> Self {
+		let mut map = BTreeMap::new();
+		for (name, value) in column_names(&row).into_iter().zip(row) {
+			map.entry(name).or_insert_with(|| value.into_owned());
+		}
+
+		map
+	}
+}
+
+/// Values of the row, by column name, in column order.
+///
+/// If several columns have the same name, the first one is kept.
+#[cfg(feature = "indexmap")]
+impl FromRow for indexmap::IndexMap<String, OwnedValue> {
+	fn from<'a, R: RowValues<'a>>(row: R) -> Self {
+		let mut map = indexmap::IndexMap::new();
+		for (name, value) in column_names(&row).into_iter().zip(row) {
+			map.entry(name).or_insert_with(|| value.into_owned());
+		}
+
+		map
+	}
+}
+
+/// Column designation, by index (`usize`) or name (`&str`).
+pub trait ColumnIndex {
+	/// Index of the designated column in the given row, if any.
+	fn index(&self, row: &Row) -> Option<usize>;
+
+	/// Description of the designated column, used in error messages.
+	fn describe(&self) -> String;
+}
+
+impl ColumnIndex for usize {
+	fn index(&self, row: &Row) -> Option<usize> {
+		if *self < row.column_count() {
+			Some(*self)
+		} else {
+			None
+		}
+	}
+
+	fn describe(&self) -> String {
+		format!("at index {}", self)
+	}
+}
+
+impl ColumnIndex for str {
+	fn index(&self, row: &Row) -> Option<usize> {
+		row.column_index(self)
+	}
+
+	fn describe(&self) -> String {
+		format!("`{}`", self)
+	}
+}
+
+impl ColumnIndex for String {
+	fn index(&self, row: &Row) -> Option<usize> {
+		row.column_index(self)
+	}
+
+	fn describe(&self) -> String {
+		self.as_str().describe()
+	}
+}
+
+impl<I: ColumnIndex + ?Sized> ColumnIndex for &I {
+	fn index(&self, row: &Row) -> Option<usize> {
+		(**self).index(row)
+	}
+
+	fn describe(&self) -> String {
+		(**self).describe()
+	}
+}
+
 pub struct Rows<'a, R> {
 	inner: Pin<Box<dyn 'a + Stream<Item = Result<R>>>>
 }
@@ -59,10 +286,10 @@ impl<'a, R> Rows<'a, R> {
 		}
 	}
 
-	pub(crate) unsafe fn into_owned<'r, S>(self, stmt: S) -> OwnedRows<'r, S, R> {
+	pub(crate) unsafe fn into_owned<'r, S>(self, stmt: Box<S>) -> OwnedRows<'r, S, R> {
 		OwnedRows {
-			statement: stmt,
-			inner: std::mem::transmute(self.inner)
+			inner: std::mem::transmute(self.inner),
+			statement: stmt
 		}
 	}
 }
@@ -76,14 +303,16 @@ impl<'a, R> Stream for Rows<'a, R> {
 }
 
 pub struct OwnedRows<'a, S, R> {
+	/// The rows stream.
+	///
+	/// Declared first so that it is dropped before the statement.
+	inner: Pin<Box<dyn 'a + Stream<Item = Result<R>>>>,
+
 	/// Statement beeing run.
 	///
 	/// It is never actually used, but must live until the rows are dropped.
 	#[allow(dead_code)]
-	statement: S,
-
-	/// The rows stream.
-	inner: Pin<Box<dyn 'a + Stream<Item = Result<R>>>>
+	statement: Box<S>
 }
 
 impl<'a, S, R> Unpin for OwnedRows<'a, S, R> { }
@@ -0,0 +1,325 @@
+//! Parameter serialization using serde.
+use std::convert::TryFrom;
+use mown::Mown;
+use serde_json::value::Serializer as JsonSerializer;
+use serde::ser::{
+	self,
+	Serialize,
+	Serializer,
+	Impossible
+};
+use crate::{
+	Value,
+	OwnedValue,
+	Result,
+	Error,
+	ErrorKind
+};
+
+/// Serialize the given value into an SQL value.
+///
+/// Booleans are serialized as integers, unit variants as their name,
+/// and nested structures (sequences, maps, structs and variants with data) as JSON text.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<OwnedValue> {
+	value.serialize(ValueSerializer)
+}
+
+/// Serialize the given struct or map into a list of named values.
+///
+/// Each field is serialized using [`to_value`].
+pub fn to_named_values<T: Serialize + ?Sized>(value: &T) -> Result<Vec<(String, OwnedValue)>> {
+	value.serialize(NamedSerializer)
+}
+
+fn json_text(value: serde_json::Value) -> Result<OwnedValue> {
+	Ok(Value::Text(Mown::Owned(value.to_string())))
+}
+
+/// Serializer of a single value.
+struct ValueSerializer;
+
+impl Serializer for ValueSerializer {
+	type Ok = OwnedValue;
+	type Error = Error;
+
+	type SerializeSeq = Json<<JsonSerializer as Serializer>::SerializeSeq>;
+	type SerializeTuple = Json<<JsonSerializer as Serializer>::SerializeTuple>;
+	type SerializeTupleStruct = Json<<JsonSerializer as Serializer>::SerializeTupleStruct>;
+	type SerializeTupleVariant = Json<<JsonSerializer as Serializer>::SerializeTupleVariant>;
+	type SerializeMap = Json<<JsonSerializer as Serializer>::SerializeMap>;
+	type SerializeStruct = Json<<JsonSerializer as Serializer>::SerializeStruct>;
+	type SerializeStructVariant = Json<<JsonSerializer as Serializer>::SerializeStructVariant>;
+
+	fn serialize_bool(self, b: bool) -> Result<OwnedValue> {
+		Ok(Value::from(b))
+	}
+
+	fn serialize_i8(self, i: i8) -> Result<OwnedValue> {
+		Ok(Value::from(i))
+	}
+
+	fn serialize_i16(self, i: i16) -> Result<OwnedValue> {
+		Ok(Value::from(i))
+	}
+
+	fn serialize_i32(self, i: i32) -> Result<OwnedValue> {
+		Ok(Value::from(i))
+	}
+
+	fn serialize_i64(self, i: i64) -> Result<OwnedValue> {
+		Ok(Value::from(i))
+	}
+
+	fn serialize_u8(self, i: u8) -> Result<OwnedValue> {
+		Ok(Value::from(i))
+	}
+
+	fn serialize_u16(self, i: u16) -> Result<OwnedValue> {
+		Ok(Value::from(i))
+	}
+
+	fn serialize_u32(self, i: u32) -> Result<OwnedValue> {
+		Ok(Value::from(i))
+	}
+
+	fn serialize_u64(self, i: u64) -> Result<OwnedValue> {
+		let i = i64::try_from(i).map_err(|_| ErrorKind::Conversion(format!("integer {} out of range", i)).err())?;
+		Ok(Value::Integer(i))
+	}
+
+	fn serialize_f32(self, f: f32) -> Result<OwnedValue> {
+		Ok(Value::from(f))
+	}
+
+	fn serialize_f64(self, f: f64) -> Result<OwnedValue> {
+		Ok(Value::from(f))
+	}
+
+	fn serialize_char(self, c: char) -> Result<OwnedValue> {
+		Ok(Value::from(c.to_string()))
+	}
+
+	fn serialize_str(self, str: &str) -> Result<OwnedValue> {
+		Ok(Value::from(str.to_string()))
+	}
+
+	fn serialize_bytes(self, bytes: &[u8]) -> Result<OwnedValue> {
+		Ok(Value::from(bytes.to_vec()))
+	}
+
+	fn serialize_none(self) -> Result<OwnedValue> {
+		Ok(Value::Null)
+	}
+
+	fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<OwnedValue> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<OwnedValue> {
+		Ok(Value::Null)
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<OwnedValue> {
+		Ok(Value::Null)
+	}
+
+	fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<OwnedValue> {
+		Ok(Value::from(variant.to_string()))
+	}
+
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<OwnedValue> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(self, name: &'static str, index: u32, variant: &'static str, value: &T) -> Result<OwnedValue> {
+		json_text(JsonSerializer.serialize_newtype_variant(name, index, variant, value)?)
+	}
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+		Ok(Json(JsonSerializer.serialize_seq(len)?))
+	}
+
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+		Ok(Json(JsonSerializer.serialize_tuple(len)?))
+	}
+
+	fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct> {
+		Ok(Json(JsonSerializer.serialize_tuple_struct(name, len)?))
+	}
+
+	fn serialize_tuple_variant(self, name: &'static str, index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeTupleVariant> {
+		Ok(Json(JsonSerializer.serialize_tuple_variant(name, index, variant, len)?))
+	}
+
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+		Ok(Json(JsonSerializer.serialize_map(len)?))
+	}
+
+	fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+		Ok(Json(JsonSerializer.serialize_struct(name, len)?))
+	}
+
+	fn serialize_struct_variant(self, name: &'static str, index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeStructVariant> {
+		Ok(Json(JsonSerializer.serialize_struct_variant(name, index, variant, len)?))
+	}
+}
+
+/// Nested structure, serialized as JSON text.
+struct Json<S>(S);
+
+macro_rules! json_compound {
+	($($trait:ident { $($method:ident ( $($arg:ident : $ty:ty),* ))* })*) => {
+		$(
+			impl<S: ser::$trait<Ok = serde_json::Value, Error = serde_json::Error>> ser::$trait for Json<S> {
+				type Ok = OwnedValue;
+				type Error = Error;
+
+				$(
+					fn $method<T: Serialize + ?Sized>(&mut self, $($arg: $ty,)* value: &T) -> Result<()> {
+						Ok(self.0.$method($($arg,)* value)?)
+					}
+				)*
+
+				fn end(self) -> Result<OwnedValue> {
+					json_text(self.0.end()?)
+				}
+			}
+		)*
+	};
+}
+
+json_compound! {
+	SerializeSeq { serialize_element() }
+	SerializeTuple { serialize_element() }
+	SerializeTupleStruct { serialize_field() }
+	SerializeTupleVariant { serialize_field() }
+	SerializeMap { serialize_key() serialize_value() }
+	SerializeStruct { serialize_field(key: &'static str) }
+	SerializeStructVariant { serialize_field(key: &'static str) }
+}
+
+/// Serializer of a struct or map into named values.
+struct NamedSerializer;
+
+fn expected_struct() -> Error {
+	ErrorKind::Conversion("expected a struct or map".to_string()).err()
+}
+
+macro_rules! unsupported {
+	($($method:ident ( $($arg:ident : $ty:ty),* ) -> $ret:ty;)*) => {
+		$(
+			fn $method(self, $(_: $ty),*) -> Result<$ret> {
+				Err(expected_struct())
+			}
+		)*
+	};
+}
+
+impl Serializer for NamedSerializer {
+	type Ok = Vec<(String, OwnedValue)>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Self::Ok, Error>;
+	type SerializeTuple = Impossible<Self::Ok, Error>;
+	type SerializeTupleStruct = Impossible<Self::Ok, Error>;
+	type SerializeTupleVariant = Impossible<Self::Ok, Error>;
+	type SerializeMap = NamedValues;
+	type SerializeStruct = NamedValues;
+	type SerializeStructVariant = Impossible<Self::Ok, Error>;
+
+	unsupported! {
+		serialize_bool(b: bool) -> Self::Ok;
+		serialize_i8(i: i8) -> Self::Ok;
+		serialize_i16(i: i16) -> Self::Ok;
+		serialize_i32(i: i32) -> Self::Ok;
+		serialize_i64(i: i64) -> Self::Ok;
+		serialize_u8(i: u8) -> Self::Ok;
+		serialize_u16(i: u16) -> Self::Ok;
+		serialize_u32(i: u32) -> Self::Ok;
+		serialize_u64(i: u64) -> Self::Ok;
+		serialize_f32(f: f32) -> Self::Ok;
+		serialize_f64(f: f64) -> Self::Ok;
+		serialize_char(c: char) -> Self::Ok;
+		serialize_str(s: &str) -> Self::Ok;
+		serialize_bytes(b: &[u8]) -> Self::Ok;
+		serialize_none() -> Self::Ok;
+		serialize_unit() -> Self::Ok;
+		serialize_unit_struct(name: &'static str) -> Self::Ok;
+		serialize_unit_variant(name: &'static str, index: u32, variant: &'static str) -> Self::Ok;
+		serialize_seq(len: Option<usize>) -> Self::SerializeSeq;
+		serialize_tuple(len: usize) -> Self::SerializeTuple;
+		serialize_tuple_struct(name: &'static str, len: usize) -> Self::SerializeTupleStruct;
+		serialize_tuple_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeTupleVariant;
+		serialize_struct_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeStructVariant;
+	}
+
+	fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Self::Ok> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _index: u32, _variant: &'static str, _value: &T) -> Result<Self::Ok> {
+		Err(expected_struct())
+	}
+
+	fn serialize_map(self, len: Option<usize>) -> Result<NamedValues> {
+		Ok(NamedValues {
+			values: Vec::with_capacity(len.unwrap_or_default()),
+			key: None
+		})
+	}
+
+	fn serialize_struct(self, _name: &'static str, len: usize) -> Result<NamedValues> {
+		Ok(NamedValues {
+			values: Vec::with_capacity(len),
+			key: None
+		})
+	}
+}
+
+struct NamedValues {
+	values: Vec<(String, OwnedValue)>,
+	key: Option<String>
+}
+
+impl ser::SerializeMap for NamedValues {
+	type Ok = Vec<(String, OwnedValue)>;
+	type Error = Error;
+
+	fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+		match to_value(key)? {
+			Value::Text(key) => {
+				self.key = Some(key.to_string());
+				Ok(())
+			},
+			_ => Err(ErrorKind::Conversion("map keys must be strings".to_string()).err())
+		}
+	}
+
+	fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+		let key = self.key.take().expect("value serialized before key");
+		self.values.push((key, to_value(value)?));
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		Ok(self.values)
+	}
+}
+
+impl ser::SerializeStruct for NamedValues {
+	type Ok = Vec<(String, OwnedValue)>;
+	type Error = Error;
+
+	fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()> {
+		self.values.push((key.to_string(), to_value(value)?));
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		Ok(self.values)
+	}
+}
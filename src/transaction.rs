@@ -9,7 +9,8 @@ use crate::{
 	Result,
 	FromRow,
 	Value,
-	Rows
+	Rows,
+	Execution
 };
 
 pub trait TransactionCapable: Connection {
@@ -81,7 +82,11 @@ impl<'a, C: Connection> Connection for Transaction<'a, C> {
 		self.connection.prepare_list(sql)
 	}
 
-	fn execute<'s, R: 's + FromRow>(&'s mut self, statement: &'s Self::Statement, args: Vec<Value>) -> LocalBoxFuture<'s, Result<Option<Rows<'s, R>>>> {
+	fn execute_script<'s>(&'s mut self, sql: &'s str) -> LocalBoxFuture<'s, Result<()>> where Self::Statement: 's {
+		self.connection.execute_script(sql)
+	}
+
+	fn execute<'s, R: 's + FromRow>(&'s mut self, statement: &'s Self::Statement, args: Vec<Value>) -> LocalBoxFuture<'s, Result<Execution<Rows<'s, R>>>> {
 		self.connection.execute(statement, args)
 	}
 }
@@ -1,5 +1,25 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{
+	Hash,
+	Hasher
+};
+use std::rc::Rc;
+use std::sync::Arc;
 use mown::Mown;
 
+mod ip;
+#[cfg(feature = "uuid")]
+mod uuid;
+#[cfg(feature = "rust_decimal")]
+mod decimal;
+#[cfg(feature = "ipnet")]
+mod ipnet;
+#[cfg(feature = "url")]
+mod url;
+
 pub enum Value<'a> {
 	Integer(i64),
 	Float(f64),
@@ -8,41 +28,185 @@ pub enum Value<'a> {
 	Null
 }
 
-pub trait FromValue: Sized {
-	fn from<'a>(value: Value<'a>) -> Self;
+/// Value that does not borrow any data.
+pub type OwnedValue = Value<'static>;
+
+impl<'a> Value<'a> {
+	/// Copy any borrowed text or blob.
+	pub fn into_owned(self) -> OwnedValue {
+		match self {
+			Value::Integer(i) => Value::Integer(i),
+			Value::Float(f) => Value::Float(f),
+			Value::Text(Mown::Borrowed(str)) => Value::Text(Mown::Owned(str.to_string())),
+			Value::Text(Mown::Owned(str)) => Value::Text(Mown::Owned(str)),
+			Value::Blob(Mown::Borrowed(bytes)) => Value::Blob(Mown::Owned(bytes.to_vec())),
+			Value::Blob(Mown::Owned(bytes)) => Value::Blob(Mown::Owned(bytes)),
+			Value::Null => Value::Null
+		}
+	}
+
+	/// Copy the value, borrowing any text or blob from this value.
+	pub fn borrow(&self) -> Value<'_> {
+		match self {
+			Value::Integer(i) => Value::Integer(*i),
+			Value::Float(f) => Value::Float(*f),
+			Value::Text(str) => Value::Text(Mown::Borrowed(str)),
+			Value::Blob(bytes) => Value::Blob(Mown::Borrowed(bytes)),
+			Value::Null => Value::Null
+		}
+	}
+
+	/// Checks if this value is `NULL`.
+	///
+	/// A NaN float is considered `NULL`, since this is how SQLite stores it.
+	pub fn is_null(&self) -> bool {
+		match self {
+			Value::Null => true,
+			Value::Float(f) => f.is_nan(),
+			_ => false
+		}
+	}
+
+	/// Class of the value in the SQLite sort order.
+	fn class(&self) -> u8 {
+		match self {
+			_ if self.is_null() => 0,
+			Value::Integer(_) | Value::Float(_) => 1,
+			Value::Text(_) => 2,
+			Value::Blob(_) => 3,
+			Value::Null => 0
+		}
+	}
+
+	/// Compare two values following the SQLite sort order.
+	fn cmp_value(&self, other: &Value) -> Ordering {
+		match self.class().cmp(&other.class()) {
+			Ordering::Equal => match (self, other) {
+				(Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+				(Value::Integer(a), Value::Float(b)) => compare_integer_float(*a, *b),
+				(Value::Float(a), Value::Integer(b)) => compare_integer_float(*b, *a).reverse(),
+				(Value::Float(a), Value::Float(b)) => a.partial_cmp(b).unwrap(),
+				(Value::Text(a), Value::Text(b)) => a.as_bytes().cmp(b.as_bytes()),
+				(Value::Blob(a), Value::Blob(b)) => (**a).cmp(&**b),
+				_ => Ordering::Equal
+			},
+			ordering => ordering
+		}
+	}
 }
 
-impl FromValue for () {
-	fn from<'a>(_value: Value<'a>) -> Self {
-		()
+// Bounds of the `i64` range, exactly representable as floats.
+const I64_MIN: f64 = -9223372036854775808.0;
+const I64_END: f64 = 9223372036854775808.0;
+
+/// Compare an integer with a (non-NaN) float, without loss of precision.
+fn compare_integer_float(i: i64, f: f64) -> Ordering {
+	if f < I64_MIN {
+		Ordering::Greater
+	} else if f >= I64_END {
+		Ordering::Less
+	} else {
+		match i.cmp(&(f.trunc() as i64)) {
+			Ordering::Equal => 0.0.partial_cmp(&f.fract()).unwrap(),
+			ordering => ordering
+		}
 	}
 }
 
-impl FromValue for usize {
-	fn from<'a>(value: Value<'a>) -> Self {
-		match value {
-			Value::Integer(i) if i >= 0 => i as usize,
-			_ => panic!("invalid convertion")
+impl<'a> Clone for Value<'a> {
+	fn clone(&self) -> Self {
+		match self {
+			Value::Integer(i) => Value::Integer(*i),
+			Value::Float(f) => Value::Float(*f),
+			Value::Text(Mown::Borrowed(str)) => Value::Text(Mown::Borrowed(str)),
+			Value::Text(Mown::Owned(str)) => Value::Text(Mown::Owned(str.clone())),
+			Value::Blob(Mown::Borrowed(bytes)) => Value::Blob(Mown::Borrowed(bytes)),
+			Value::Blob(Mown::Owned(bytes)) => Value::Blob(Mown::Owned(bytes.clone())),
+			Value::Null => Value::Null
 		}
 	}
 }
 
-impl FromValue for u32 {
-	fn from<'a>(value: Value<'a>) -> Self {
-		match value {
-			Value::Integer(i) if i >= 0 => i as u32,
-			_ => panic!("invalid convertion")
+impl<'a> fmt::Debug for Value<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Value::Integer(i) => f.debug_tuple("Integer").field(i).finish(),
+			Value::Float(x) => f.debug_tuple("Float").field(x).finish(),
+			Value::Text(str) => f.debug_tuple("Text").field(&&**str).finish(),
+			Value::Blob(bytes) => f.debug_tuple("Blob").field(&&**bytes).finish(),
+			Value::Null => write!(f, "Null")
+		}
+	}
+}
+
+/// Values are compared following the SQLite sort order.
+///
+/// `NULL` values come first, then numeric values (integers and floats compare by their numeric value),
+/// then text and blob values, compared byte by byte.
+impl<'a, 'b> PartialEq<Value<'b>> for Value<'a> {
+	fn eq(&self, other: &Value<'b>) -> bool {
+		self.cmp_value(other) == Ordering::Equal
+	}
+}
+
+impl<'a> Eq for Value<'a> { }
+
+impl<'a, 'b> PartialOrd<Value<'b>> for Value<'a> {
+	fn partial_cmp(&self, other: &Value<'b>) -> Option<Ordering> {
+		Some(self.cmp_value(other))
+	}
+}
+
+impl<'a> Ord for Value<'a> {
+	fn cmp(&self, other: &Value<'a>) -> Ordering {
+		self.cmp_value(other)
+	}
+}
+
+/// Consistent with equality: an integral float has the same hash as the equal integer.
+impl<'a> Hash for Value<'a> {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.class().hash(state);
+		match self {
+			_ if self.is_null() => (),
+			Value::Integer(i) => i.hash(state),
+			Value::Float(f) => {
+				if f.fract() == 0.0 && *f >= I64_MIN && *f < I64_END {
+					(*f as i64).hash(state)
+				} else {
+					f.to_bits().hash(state)
+				}
+			},
+			Value::Text(str) => str.as_bytes().hash(state),
+			Value::Blob(bytes) => (**bytes).hash(state),
+			Value::Null => ()
 		}
 	}
 }
 
-impl FromValue for i32 {
+pub trait FromValue: Sized {
+	fn from<'a>(value: Value<'a>) -> Self;
+
+	/// Checks if the given value can be converted without panicking.
+	///
+	/// This is used by fallible getters, such as [`Row::try_get`](crate::Row::try_get).
+	/// By default, every value is accepted.
+	fn accepts(_value: &Value) -> bool {
+		true
+	}
+}
+
+impl FromValue for usize {
 	fn from<'a>(value: Value<'a>) -> Self {
 		match value {
-			Value::Integer(i) => i as i32,
+			Value::Integer(i) if i >= 0 => i as usize,
 			_ => panic!("invalid convertion")
 		}
 	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Integer(i) if *i >= 0)
+	}
 }
 
 impl FromValue for u64 {
@@ -52,6 +216,10 @@ impl FromValue for u64 {
 			_ => panic!("invalid convertion")
 		}
 	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Integer(i) if *i >= 0)
+	}
 }
 
 impl FromValue for i64 {
@@ -61,24 +229,38 @@ impl FromValue for i64 {
 			_ => panic!("invalid convertion")
 		}
 	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Integer(_))
+	}
 }
 
 impl FromValue for f32 {
 	fn from<'a>(value: Value<'a>) -> Self {
 		match value {
 			Value::Float(f) => f as f32,
+			Value::Integer(i) => i as f32,
 			_ => panic!("invalid convertion")
 		}
 	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Integer(_) | Value::Float(_))
+	}
 }
 
 impl FromValue for f64 {
 	fn from<'a>(value: Value<'a>) -> Self {
 		match value {
 			Value::Float(f) => f,
+			Value::Integer(i) => i as f64,
 			_ => panic!("invalid convertion")
 		}
 	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Integer(_) | Value::Float(_))
+	}
 }
 
 impl FromValue for String {
@@ -89,6 +271,139 @@ impl FromValue for String {
 			_ => panic!("invalid convertion")
 		}
 	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Text(_))
+	}
+}
+
+impl FromValue for bool {
+	fn from<'a>(value: Value<'a>) -> Self {
+		match value {
+			Value::Integer(i) => i != 0,
+			_ => panic!("invalid conversion")
+		}
+	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Integer(_))
+	}
+}
+
+/// Integer types that may not hold every SQLite integer.
+macro_rules! narrow_integer_from_value {
+	($($t:ty),*) => {
+		$(
+			impl FromValue for $t {
+				fn from<'a>(value: Value<'a>) -> Self {
+					match value {
+						Value::Integer(i) => <$t>::try_from(i).expect("integer out of range"),
+						_ => panic!("invalid conversion")
+					}
+				}
+
+				fn accepts(value: &Value) -> bool {
+					matches!(value, Value::Integer(i) if <$t>::try_from(*i).is_ok())
+				}
+			}
+		)*
+	};
+}
+
+narrow_integer_from_value!(i8, i16, i32, u8, u16, u32, isize);
+
+impl FromValue for Box<str> {
+	fn from<'a>(value: Value<'a>) -> Self {
+		<String as FromValue>::from(value).into_boxed_str()
+	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Text(_))
+	}
+}
+
+impl FromValue for Rc<str> {
+	fn from<'a>(value: Value<'a>) -> Self {
+		<String as FromValue>::from(value).into()
+	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Text(_))
+	}
+}
+
+impl FromValue for Arc<str> {
+	fn from<'a>(value: Value<'a>) -> Self {
+		<String as FromValue>::from(value).into()
+	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Text(_))
+	}
+}
+
+impl FromValue for Cow<'static, str> {
+	fn from<'a>(value: Value<'a>) -> Self {
+		Cow::Owned(<String as FromValue>::from(value))
+	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Text(_))
+	}
+}
+
+impl FromValue for Vec<u8> {
+	fn from<'a>(value: Value<'a>) -> Self {
+		match value {
+			Value::Blob(Mown::Borrowed(bytes)) => bytes.to_vec(),
+			Value::Blob(Mown::Owned(bytes)) => bytes,
+			_ => panic!("invalid conversion")
+		}
+	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Blob(_))
+	}
+}
+
+impl FromValue for Box<[u8]> {
+	fn from<'a>(value: Value<'a>) -> Self {
+		<Vec<u8> as FromValue>::from(value).into_boxed_slice()
+	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Blob(_))
+	}
+}
+
+impl FromValue for Rc<[u8]> {
+	fn from<'a>(value: Value<'a>) -> Self {
+		<Vec<u8> as FromValue>::from(value).into()
+	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Blob(_))
+	}
+}
+
+impl FromValue for Arc<[u8]> {
+	fn from<'a>(value: Value<'a>) -> Self {
+		<Vec<u8> as FromValue>::from(value).into()
+	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Blob(_))
+	}
+}
+
+impl FromValue for Cow<'static, [u8]> {
+	fn from<'a>(value: Value<'a>) -> Self {
+		Cow::Owned(<Vec<u8> as FromValue>::from(value))
+	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Blob(_))
+	}
 }
 
 impl FromValue for chrono::NaiveDate {
@@ -98,6 +413,10 @@ impl FromValue for chrono::NaiveDate {
 			_ => panic!("invalid convertion")
 		}
 	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Text(str) if chrono::NaiveDate::parse_from_str(str, "%Y-%m-%d").is_ok())
+	}
 }
 
 impl FromValue for chrono::NaiveTime {
@@ -107,6 +426,10 @@ impl FromValue for chrono::NaiveTime {
 			_ => panic!("invalid convertion")
 		}
 	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Text(str) if chrono::NaiveTime::parse_from_str(str, "%H:%M:%S%.f").is_ok())
+	}
 }
 
 impl FromValue for chrono::NaiveDateTime {
@@ -116,6 +439,16 @@ impl FromValue for chrono::NaiveDateTime {
 			_ => panic!("invalid convertion")
 		}
 	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Text(str) if chrono::NaiveDateTime::parse_from_str(str, "%+").is_ok())
+	}
+}
+
+impl FromValue for OwnedValue {
+	fn from<'a>(value: Value<'a>) -> Self {
+		value.into_owned()
+	}
 }
 
 impl<T: FromValue> FromValue for Option<T> {
@@ -125,6 +458,10 @@ impl<T: FromValue> FromValue for Option<T> {
 			some => Some(T::from(some))
 		}
 	}
+
+	fn accepts(value: &Value) -> bool {
+		value.is_null() || T::accepts(value)
+	}
 }
 
 impl<'a> From<usize> for Value<'a> {
@@ -169,6 +506,116 @@ impl<'a> From<&'a str> for Value<'a> {
 	}
 }
 
+impl<'a> From<bool> for Value<'a> {
+	fn from(b: bool) -> Value<'a> {
+		Value::Integer(b as i64)
+	}
+}
+
+macro_rules! integer_into_value {
+	($($t:ty),*) => {
+		$(
+			impl<'a> From<$t> for Value<'a> {
+				fn from(i: $t) -> Value<'a> {
+					Value::Integer(i as i64)
+				}
+			}
+		)*
+	};
+}
+
+integer_into_value!(i8, i16, u8, u16, isize);
+
+impl<'a> From<f32> for Value<'a> {
+	fn from(f: f32) -> Value<'a> {
+		Value::Float(f as f64)
+	}
+}
+
+impl<'a> From<f64> for Value<'a> {
+	fn from(f: f64) -> Value<'a> {
+		Value::Float(f)
+	}
+}
+
+impl<'a> From<&'a String> for Value<'a> {
+	fn from(str: &'a String) -> Value<'a> {
+		Value::Text(Mown::Borrowed(str))
+	}
+}
+
+impl<'a> From<Box<str>> for Value<'a> {
+	fn from(str: Box<str>) -> Value<'a> {
+		Value::Text(Mown::Owned(str.into_string()))
+	}
+}
+
+impl<'a> From<Rc<str>> for Value<'a> {
+	fn from(str: Rc<str>) -> Value<'a> {
+		Value::Text(Mown::Owned(str.to_string()))
+	}
+}
+
+impl<'a> From<Arc<str>> for Value<'a> {
+	fn from(str: Arc<str>) -> Value<'a> {
+		Value::Text(Mown::Owned(str.to_string()))
+	}
+}
+
+impl<'a> From<Cow<'a, str>> for Value<'a> {
+	fn from(str: Cow<'a, str>) -> Value<'a> {
+		match str {
+			Cow::Borrowed(str) => Value::Text(Mown::Borrowed(str)),
+			Cow::Owned(str) => Value::Text(Mown::Owned(str))
+		}
+	}
+}
+
+impl<'a> From<Vec<u8>> for Value<'a> {
+	fn from(bytes: Vec<u8>) -> Value<'a> {
+		Value::Blob(Mown::Owned(bytes))
+	}
+}
+
+impl<'a> From<&'a [u8]> for Value<'a> {
+	fn from(bytes: &'a [u8]) -> Value<'a> {
+		Value::Blob(Mown::Borrowed(bytes))
+	}
+}
+
+impl<'a> From<&'a Vec<u8>> for Value<'a> {
+	fn from(bytes: &'a Vec<u8>) -> Value<'a> {
+		Value::Blob(Mown::Borrowed(bytes))
+	}
+}
+
+impl<'a> From<Box<[u8]>> for Value<'a> {
+	fn from(bytes: Box<[u8]>) -> Value<'a> {
+		Value::Blob(Mown::Owned(bytes.into_vec()))
+	}
+}
+
+impl<'a> From<Rc<[u8]>> for Value<'a> {
+	fn from(bytes: Rc<[u8]>) -> Value<'a> {
+		Value::Blob(Mown::Owned(bytes.to_vec()))
+	}
+}
+
+impl<'a> From<Arc<[u8]>> for Value<'a> {
+	fn from(bytes: Arc<[u8]>) -> Value<'a> {
+		Value::Blob(Mown::Owned(bytes.to_vec()))
+	}
+}
+
+impl<'a> From<Cow<'a, [u8]>> for Value<'a> {
+	fn from(bytes: Cow<'a, [u8]>) -> Value<'a> {
+		match bytes {
+			Cow::Borrowed(bytes) => Value::Blob(Mown::Borrowed(bytes)),
+			Cow::Owned(bytes) => Value::Blob(Mown::Owned(bytes))
+		}
+	}
+}
+
 impl<'a> From<chrono::NaiveDate> for Value<'a> {
 	fn from(date: chrono::NaiveDate) -> Value<'a> {
 		Value::Text(Mown::Owned(date.format("%Y-%m-%d").to_string()))
@@ -185,4 +632,45 @@ impl<'a> From<chrono::NaiveDateTime> for Value<'a> {
 	fn from(date: chrono::NaiveDateTime) -> Value<'a> {
 		Value::Text(Mown::Owned(date.format("%+").to_string()))
 	}
-}
\ No newline at end of file
+}
+
+/// Value stored as text, for types that are stored as blobs by default.
+///
+/// Converting from a column value accepts any representation supported by `T`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct AsText<T>(pub T);
+
+/// Value stored as a blob, for types that are stored as text by default.
+///
+/// Converting from a column value accepts any representation supported by `T`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct AsBlob<T>(pub T);
+
+macro_rules! representation_wrapper {
+	($($ty:ident),*) => {
+		$(
+			impl<T: FromValue> FromValue for $ty<T> {
+				fn from<'a>(value: Value<'a>) -> Self {
+					$ty(T::from(value))
+				}
+
+				fn accepts(value: &Value) -> bool {
+					T::accepts(value)
+				}
+			}
+		)*
+	};
+}
+
+wrapper!(AsText, AsBlob);
+representation_wrapper!(AsText, AsBlob);
+
+/// `None` is converted into `NULL`.
+impl<'a, T: Into<Value<'a>>> From<Option<T>> for Value<'a> {
+	fn from(value: Option<T>) -> Value<'a> {
+		match value {
+			Some(value) => value.into(),
+			None => Value::Null
+		}
+	}
+}
@@ -0,0 +1,37 @@
+use std::convert::TryFrom;
+use mown::Mown;
+use rust_decimal::Decimal;
+use super::{
+	Value,
+	FromValue
+};
+
+/// Text, integer or float.
+///
+/// Floats are converted approximately, use text columns to keep exact values.
+impl FromValue for Decimal {
+	fn from<'a>(value: Value<'a>) -> Self {
+		match value {
+			Value::Text(str) => str.parse().expect("invalid decimal"),
+			Value::Integer(i) => Decimal::new(i, 0),
+			Value::Float(f) => Decimal::try_from(f).expect("invalid decimal"),
+			_ => panic!("invalid conversion")
+		}
+	}
+
+	fn accepts(value: &Value) -> bool {
+		match value {
+			Value::Text(str) => str.parse::<Decimal>().is_ok(),
+			Value::Integer(_) => true,
+			Value::Float(f) => Decimal::try_from(*f).is_ok(),
+			_ => false
+		}
+	}
+}
+
+/// Stored as text.
+impl<'a> From<Decimal> for Value<'a> {
+	fn from(d: Decimal) -> Value<'a> {
+		Value::Text(Mown::Owned(d.to_string()))
+	}
+}
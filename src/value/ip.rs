@@ -0,0 +1,106 @@
+use std::convert::TryFrom;
+use std::net::{
+	IpAddr,
+	Ipv4Addr,
+	Ipv6Addr
+};
+use mown::Mown;
+use super::{
+	Value,
+	FromValue,
+	AsBlob
+};
+
+impl FromValue for Ipv4Addr {
+	fn from<'a>(value: Value<'a>) -> Self {
+		match value {
+			Value::Text(str) => str.parse().expect("invalid IPv4 address"),
+			Value::Blob(bytes) => <[u8; 4]>::try_from(&*bytes).expect("invalid IPv4 address").into(),
+			_ => panic!("invalid conversion")
+		}
+	}
+
+	fn accepts(value: &Value) -> bool {
+		match value {
+			Value::Text(str) => str.parse::<Ipv4Addr>().is_ok(),
+			Value::Blob(bytes) => bytes.len() == 4,
+			_ => false
+		}
+	}
+}
+
+impl FromValue for Ipv6Addr {
+	fn from<'a>(value: Value<'a>) -> Self {
+		match value {
+			Value::Text(str) => str.parse().expect("invalid IPv6 address"),
+			Value::Blob(bytes) => <[u8; 16]>::try_from(&*bytes).expect("invalid IPv6 address").into(),
+			_ => panic!("invalid conversion")
+		}
+	}
+
+	fn accepts(value: &Value) -> bool {
+		match value {
+			Value::Text(str) => str.parse::<Ipv6Addr>().is_ok(),
+			Value::Blob(bytes) => bytes.len() == 16,
+			_ => false
+		}
+	}
+}
+
+/// Text, or blob of 4 (IPv4) or 16 (IPv6) bytes.
+impl FromValue for IpAddr {
+	fn from<'a>(value: Value<'a>) -> Self {
+		match value {
+			Value::Text(str) => str.parse().expect("invalid IP address"),
+			Value::Blob(bytes) => match bytes.len() {
+				4 => IpAddr::V4(<[u8; 4]>::try_from(&*bytes).unwrap().into()),
+				16 => IpAddr::V6(<[u8; 16]>::try_from(&*bytes).unwrap().into()),
+				_ => panic!("invalid IP address")
+			},
+			_ => panic!("invalid conversion")
+		}
+	}
+
+	fn accepts(value: &Value) -> bool {
+		match value {
+			Value::Text(str) => str.parse::<IpAddr>().is_ok(),
+			Value::Blob(bytes) => bytes.len() == 4 || bytes.len() == 16,
+			_ => false
+		}
+	}
+}
+
+macro_rules! ip_into_value {
+	($($ty:ty),*) => {
+		$(
+			impl<'a> From<$ty> for Value<'a> {
+				fn from(ip: $ty) -> Value<'a> {
+					Value::Text(Mown::Owned(ip.to_string()))
+				}
+			}
+		)*
+	};
+}
+
+ip_into_value!(IpAddr, Ipv4Addr, Ipv6Addr);
+
+impl<'a> From<AsBlob<Ipv4Addr>> for Value<'a> {
+	fn from(ip: AsBlob<Ipv4Addr>) -> Value<'a> {
+		Value::Blob(Mown::Owned(ip.0.octets().to_vec()))
+	}
+}
+
+impl<'a> From<AsBlob<Ipv6Addr>> for Value<'a> {
+	fn from(ip: AsBlob<Ipv6Addr>) -> Value<'a> {
+		Value::Blob(Mown::Owned(ip.0.octets().to_vec()))
+	}
+}
+
+impl<'a> From<AsBlob<IpAddr>> for Value<'a> {
+	fn from(ip: AsBlob<IpAddr>) -> Value<'a> {
+		match ip.0 {
+			IpAddr::V4(ip) => AsBlob(ip).into(),
+			IpAddr::V6(ip) => AsBlob(ip).into()
+		}
+	}
+}
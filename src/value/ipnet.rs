@@ -0,0 +1,39 @@
+use mown::Mown;
+use ::ipnet::{
+	IpNet,
+	Ipv4Net,
+	Ipv6Net
+};
+use super::{
+	Value,
+	FromValue
+};
+
+macro_rules! ip_net {
+	($($ty:ty),*) => {
+		$(
+			/// Text in CIDR notation.
+			impl FromValue for $ty {
+				fn from<'a>(value: Value<'a>) -> Self {
+					match value {
+						Value::Text(str) => str.parse().expect("invalid IP network"),
+						_ => panic!("invalid conversion")
+					}
+				}
+
+				fn accepts(value: &Value) -> bool {
+					matches!(value, Value::Text(str) if str.parse::<$ty>().is_ok())
+				}
+			}
+
+			/// Stored as text in CIDR notation.
+			impl<'a> From<$ty> for Value<'a> {
+				fn from(net: $ty) -> Value<'a> {
+					Value::Text(Mown::Owned(net.to_string()))
+				}
+			}
+		)*
+	};
+}
+
+ip_net!(IpNet, Ipv4Net, Ipv6Net);
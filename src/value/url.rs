@@ -0,0 +1,26 @@
+use mown::Mown;
+use ::url::Url;
+use super::{
+	Value,
+	FromValue
+};
+
+impl FromValue for Url {
+	fn from<'a>(value: Value<'a>) -> Self {
+		match value {
+			Value::Text(str) => Url::parse(&str).expect("invalid URL"),
+			_ => panic!("invalid conversion")
+		}
+	}
+
+	fn accepts(value: &Value) -> bool {
+		matches!(value, Value::Text(str) if Url::parse(str).is_ok())
+	}
+}
+
+/// Stored as text.
+impl<'a> From<Url> for Value<'a> {
+	fn from(url: Url) -> Value<'a> {
+		Value::Text(Mown::Owned(url.into()))
+	}
+}
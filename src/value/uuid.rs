@@ -0,0 +1,41 @@
+use std::convert::TryFrom;
+use mown::Mown;
+use ::uuid::Uuid;
+use super::{
+	Value,
+	FromValue,
+	AsText
+};
+
+/// Blob of 16 bytes, or text in any format accepted by [`Uuid::parse_str`].
+impl FromValue for Uuid {
+	fn from<'a>(value: Value<'a>) -> Self {
+		match value {
+			Value::Blob(bytes) => Uuid::from_bytes(<[u8; 16]>::try_from(&*bytes).expect("invalid UUID")),
+			Value::Text(str) => Uuid::parse_str(&str).expect("invalid UUID"),
+			_ => panic!("invalid conversion")
+		}
+	}
+
+	fn accepts(value: &Value) -> bool {
+		match value {
+			Value::Blob(bytes) => bytes.len() == 16,
+			Value::Text(str) => Uuid::parse_str(str).is_ok(),
+			_ => false
+		}
+	}
+}
+
+/// Stored as a blob of 16 bytes.
+impl<'a> From<Uuid> for Value<'a> {
+	fn from(uuid: Uuid) -> Value<'a> {
+		Value::Blob(Mown::Owned(uuid.as_bytes().to_vec()))
+	}
+}
+
+/// Stored as hyphenated lowercase text.
+impl<'a> From<AsText<Uuid>> for Value<'a> {
+	fn from(uuid: AsText<Uuid>) -> Value<'a> {
+		Value::Text(Mown::Owned(uuid.0.hyphenated().to_string()))
+	}
+}
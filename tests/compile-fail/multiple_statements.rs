@@ -0,0 +1,3 @@
+sql_connect::query!(User, "tests/schema", "SELECT name FROM user; SELECT id FROM user");
+
+fn main() {}
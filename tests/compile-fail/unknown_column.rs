@@ -0,0 +1,3 @@
+sql_connect::query!(User, "tests/schema", "SELECT nme FROM user");
+
+fn main() {}
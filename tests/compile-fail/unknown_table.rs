@@ -0,0 +1,3 @@
+sql_connect::query!(User, "tests/schema", "SELECT name FROM users");
+
+fn main() {}
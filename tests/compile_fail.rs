@@ -0,0 +1,11 @@
+/// Queries rejected by the `query!` macro.
+///
+/// The test crates are built out of this crate,
+/// so schema paths are resolved against `SQL_CONNECT_SCHEMA_ROOT`.
+#[cfg(feature = "macros")]
+#[test]
+fn query_errors() {
+	std::env::set_var("SQL_CONNECT_SCHEMA_ROOT", env!("CARGO_MANIFEST_DIR"));
+	let t = trybuild::TestCases::new();
+	t.compile_fail("tests/compile-fail/*.rs");
+}
@@ -0,0 +1,6 @@
+CREATE TABLE user (
+	id INTEGER PRIMARY KEY,
+	name VARCHAR(64) NOT NULL,
+	email TEXT,
+	score REAL
+);
@@ -0,0 +1,8 @@
+CREATE TABLE post (
+	id INTEGER PRIMARY KEY,
+	author INTEGER NOT NULL REFERENCES user (id),
+	title TEXT NOT NULL,
+	content BLOB
+);
+
+ALTER TABLE user ADD COLUMN active BOOLEAN NOT NULL DEFAULT 1;
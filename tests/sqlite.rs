@@ -36,13 +36,13 @@ async fn create_table() -> sql_connect::Result<()> {
 	let mut ctx = sql_connect::sqlite::Connection::new()?;
 
 	let stmt = ctx.prepare("CREATE TABLE foo (id TEXT PRIMARY KEY)")?.unwrap();
-	assert!(ctx.execute::<()>(&stmt, vec![]).await?.is_none());
+	assert!(ctx.execute::<()>(&stmt, vec![]).await?.is_done());
 
 	let stmt = ctx.prepare("INSERT INTO foo (id) VALUES ('bar')")?.unwrap();
-	assert!(ctx.execute::<()>(&stmt, vec![]).await?.is_none());
+	assert!(ctx.execute::<()>(&stmt, vec![]).await?.is_done());
 
 	let stmt = ctx.prepare("SELECT (id) FROM foo")?.unwrap();
-	let mut rows = ctx.execute::<String>(&stmt, vec![]).await?.unwrap();
+	let mut rows = ctx.execute::<String>(&stmt, vec![]).await?.unwrap_rows();
  	let mut rows: Vec<_> = rows.collect().await;
 
 	assert_eq!(rows.len(), 1);
@@ -55,31 +55,65 @@ async fn transaction() -> sql_connect::Result<()> {
 	let mut ctx = sql_connect::sqlite::Connection::new()?;
 
 	let stmt = ctx.prepare("CREATE TABLE foo (id TEXT PRIMARY KEY)")?.unwrap();
-	assert!(ctx.execute::<()>(&stmt, vec![]).await?.is_none());
+	assert!(ctx.execute::<()>(&stmt, vec![]).await?.is_done());
 
 	let stmt = ctx.prepare("INSERT INTO foo (id) VALUES ('bar')")?.unwrap();
-	assert!(ctx.execute::<()>(&stmt, vec![]).await?.is_none());
+	assert!(ctx.execute::<()>(&stmt, vec![]).await?.is_done());
 
 	let mut trans = ctx.begin().await?;
 
 	let stmt = trans.prepare("INSERT INTO foo (id) VALUES ('biz')")?.unwrap();
-	assert!(trans.execute::<()>(&stmt, vec![]).await?.is_none());
+	assert!(trans.execute::<()>(&stmt, vec![]).await?.is_done());
 
 	let stmtt = trans.prepare("SELECT (id) FROM foo")?.unwrap();
-	let mut rows = trans.execute::<String>(&stmtt, vec![]).await?.unwrap();
+	let mut rows = trans.execute::<String>(&stmtt, vec![]).await?.unwrap_rows();
  	let mut rows: Vec<_> = rows.collect().await;
 	assert_eq!(rows.len(), 2);
 
 	trans.rollback().await?;
 
 	let stmtt = ctx.prepare("SELECT (id) FROM foo")?.unwrap();
-	let mut rows = ctx.execute::<String>(&stmtt, vec![]).await?.unwrap();
+	let mut rows = ctx.execute::<String>(&stmtt, vec![]).await?.unwrap_rows();
  	let mut rows: Vec<_> = rows.collect().await;
 	assert_eq!(rows.len(), 1);
 
 	Ok(())
 }
 
+#[async_std::test]
+async fn execution_result() -> sql_connect::Result<()> {
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+
+	let result = ctx.execute_sql::<()>("CREATE TABLE foo (id INTEGER PRIMARY KEY, name TEXT)", vec![]).await?.unwrap_done();
+	assert_eq!(result.rows_affected, 0);
+
+	let result = ctx.execute_sql::<()>("INSERT INTO foo (name) VALUES ('a'), ('b'), ('c')", vec![]).await?.unwrap_done();
+	assert_eq!(result.rows_affected, 3);
+	assert_eq!(result.last_insert_rowid, 3);
+	assert_eq!(ctx.last_insert_rowid(), 3);
+
+	let stmt = ctx.prepare("UPDATE foo SET name = 'z' WHERE id > 1")?.unwrap();
+	let result = ctx.execute::<()>(&stmt, vec![]).await?.unwrap_done();
+	assert_eq!(result.rows_affected, 2);
+
+	let result = ctx.execute_sql::<()>("DELETE FROM foo WHERE id = 1", vec![]).await?.unwrap_done();
+	assert_eq!(result.rows_affected, 1);
+
+	// A statement that does not modify any row does not report the changes of the previous one.
+	let result = ctx.execute_sql::<()>("CREATE TABLE bar (id INTEGER)", vec![]).await?.unwrap_done();
+	assert_eq!(result.rows_affected, 0);
+	assert_eq!(ctx.total_changes(), 6);
+
+	let rows: Vec<_> = ctx.execute_sql::<String>("SELECT name FROM foo", vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.into_iter().collect::<sql_connect::Result<Vec<_>>>()?, vec!["z", "z"]);
+
+	let stmt = ctx.prepare("SELECT id FROM foo")?.unwrap();
+	let rows: Vec<_> = ctx.consume::<i64>(stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.len(), 2);
+
+	Ok(())
+}
+
 // #[async_std::test]
 // async fn nested_transaction() -> sql_connect::Result<()> {
 // 	let mut ctx = sql_connect::sqlite::Connection::new()?;
@@ -92,3 +126,1368 @@ async fn transaction() -> sql_connect::Result<()> {
 //
 // 	Ok(())
 // }
+
+#[async_std::test]
+async fn serialize() -> sql_connect::Result<()> {
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE foo (id TEXT PRIMARY KEY); INSERT INTO foo (id) VALUES ('bar')").await?;
+
+	let bytes = ctx.serialize("main")?;
+	let mut copy = sql_connect::sqlite::Connection::from_bytes(&bytes)?;
+
+	let stmt = copy.prepare("SELECT id FROM foo")?.unwrap();
+	let rows: Vec<_> = copy.execute::<String>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.len(), 1);
+	assert_eq!(rows.into_iter().next().unwrap()?, "bar");
+	Ok(())
+}
+
+#[async_std::test]
+async fn serialize_empty() -> sql_connect::Result<()> {
+	let ctx = sql_connect::sqlite::Connection::new()?;
+	let bytes = ctx.serialize("main")?;
+	sql_connect::sqlite::Connection::from_bytes(&bytes)?;
+	assert!(ctx.serialize("unknown").is_err());
+	Ok(())
+}
+
+#[async_std::test]
+async fn duplicate() -> sql_connect::Result<()> {
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE foo (id TEXT PRIMARY KEY); INSERT INTO foo (id) VALUES ('bar')").await?;
+
+	ctx.execute_script("CREATE TEMP TABLE tmp (id TEXT); INSERT INTO tmp (id) VALUES ('baz')").await?;
+	ctx.attach(sql_connect::sqlite::AttachOptions::memory(), "other").await?;
+	ctx.execute_script("CREATE TABLE other.bar (id TEXT); INSERT INTO other.bar (id) VALUES ('qux')").await?;
+
+	let mut copy = ctx.duplicate().await?;
+	copy.execute_script("INSERT INTO foo (id) VALUES ('biz')").await?;
+
+	let stmt = ctx.prepare("SELECT id FROM foo")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<String>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.len(), 1);
+	let stmt = copy.prepare("SELECT id FROM foo")?.unwrap();
+	let rows: Vec<_> = copy.execute::<String>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.len(), 2);
+	assert_eq!(copy.fetch_one::<String>("SELECT id FROM tmp", vec![]).await?, "baz");
+	assert_eq!(copy.fetch_one::<String>("SELECT id FROM other.bar", vec![]).await?, "qux");
+	Ok(())
+}
+
+#[async_std::test]
+async fn deserialize_read_only() -> sql_connect::Result<()> {
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE foo (id TEXT PRIMARY KEY)").await?;
+	let bytes = ctx.serialize("main")?;
+
+	let mut copy = sql_connect::sqlite::Connection::new()?;
+	copy.deserialize("main", &bytes, true)?;
+	assert!(copy.execute_script("INSERT INTO foo (id) VALUES ('bar')").await.is_err());
+	Ok(())
+}
+
+#[async_std::test]
+async fn session() -> sql_connect::Result<()> {
+	use sql_connect::{
+		Value,
+		sqlite::{
+			Operation,
+			Conflict,
+			ConflictAction
+		}
+	};
+
+	let schema = "CREATE TABLE foo (id TEXT PRIMARY KEY, n INTEGER)";
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script(schema).await?;
+	let mut other = sql_connect::sqlite::Connection::new()?;
+	other.execute_script(schema).await?;
+	other.execute_script("INSERT INTO foo (id, n) VALUES ('biz', 0)").await?;
+
+	let (changeset, patchset) = {
+		let mut session = ctx.session("main")?;
+		session.attach(None)?;
+		assert!(session.is_empty());
+		session.execute_script("INSERT INTO foo (id, n) VALUES ('bar', 1); INSERT INTO foo (id, n) VALUES ('biz', 2)").await?;
+		assert!(!session.is_empty());
+		(session.changeset()?, session.patchset()?)
+	};
+
+	let changes = changeset.iter()?.collect::<sql_connect::Result<Vec<_>>>()?;
+	assert_eq!(changes.len(), 2);
+	for change in &changes {
+		assert_eq!(change.table, "foo");
+		assert_eq!(change.operation, Operation::Insert);
+		assert_eq!(change.primary_key, vec![true, false]);
+		assert!(change.old.is_empty());
+		assert_eq!(change.new.len(), 2);
+	}
+	assert!(changes.iter().any(|c| matches!(c.new[1], Some(Value::Integer(2)))));
+
+	let bytes = changeset.as_bytes();
+	let truncated = sql_connect::sqlite::Changeset::from_bytes(bytes[..bytes.len() - 1].to_vec());
+	let changes: Vec<_> = truncated.iter()?.collect();
+	assert!(changes.last().unwrap().is_err());
+	assert_eq!(changes.iter().filter(|c| c.is_err()).count(), 1);
+
+	let mut conflicts = 0;
+	other.apply_changeset(&changeset, |conflict| {
+		conflicts += 1;
+		match conflict {
+			Conflict::Conflict { current, .. } => {
+				assert!(matches!(current[1], Value::Integer(0)));
+				ConflictAction::Replace
+			},
+			_ => ConflictAction::Abort
+		}
+	})?;
+	assert_eq!(conflicts, 1);
+
+	let stmt = other.prepare("SELECT SUM(n) FROM foo")?.unwrap();
+	let rows: Vec<_> = other.execute::<i64>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.into_iter().next().unwrap()?, 3);
+	std::mem::drop(stmt);
+
+	assert!(patchset.invert().is_err());
+	let inverse = changeset.invert()?;
+	assert!(changeset.concat(&inverse)?.iter()?.next().is_none());
+
+	other.apply_changeset(&inverse, |_| ConflictAction::Abort)?;
+	let stmt = other.prepare("SELECT COUNT(*) FROM foo")?.unwrap();
+	let rows: Vec<_> = other.execute::<i64>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.into_iter().next().unwrap()?, 0);
+	Ok(())
+}
+
+#[async_std::test]
+async fn attach() -> sql_connect::Result<()> {
+	use sql_connect::sqlite::AttachOptions;
+
+	let path = std::env::temp_dir().join(format!("sql-connect-attach-{}.db", std::process::id()));
+	{
+		let mut file = sql_connect::sqlite::Connection::open(&path)?;
+		file.execute_script("CREATE TABLE IF NOT EXISTS foo (id TEXT PRIMARY KEY)").await?;
+	}
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.attach(AttachOptions::memory(), "odd \"name\"").await?;
+	ctx.attach(AttachOptions::new(&path).read_only(true), "file").await?;
+	assert_eq!(ctx.attachments().len(), 2);
+
+	ctx.execute_script("CREATE TABLE \"odd \"\"name\"\"\".bar (id TEXT PRIMARY KEY)").await?;
+	assert!(ctx.execute_script("INSERT INTO file.foo (id) VALUES ('bar')").await.is_err());
+
+	let databases = ctx.databases().await?;
+	let schemas: Vec<_> = databases.iter().map(|db| db.schema.as_str()).collect();
+	assert_eq!(schemas, vec!["main", "odd \"name\"", "file"]);
+	assert!(databases[1].path.is_none());
+	assert!(!databases[1].read_only);
+	assert!(databases[2].path.is_some());
+	assert!(databases[2].read_only);
+
+	let mut other = sql_connect::sqlite::Connection::new()?;
+	other.attach_all(ctx.attachments()).await?;
+	assert_eq!(other.databases().await?.len(), 3);
+
+	ctx.detach("odd \"name\"").await?;
+	assert_eq!(ctx.attachments().len(), 1);
+	assert_eq!(ctx.databases().await?.len(), 2);
+
+	std::fs::remove_file(&path).ok();
+	Ok(())
+}
+
+#[async_std::test]
+async fn open_file_prefix() -> sql_connect::Result<()> {
+	use sql_connect::sqlite::AttachOptions;
+
+	// Neither interpreted as an URI filename.
+	let path = std::env::temp_dir().join(format!("file:sql-connect-open-{}.db?mode=memory", std::process::id()));
+	let attached = std::env::temp_dir().join(format!("file:sql-connect-attach-{}.db?mode=memory", std::process::id()));
+	let result = async {
+		let mut ctx = sql_connect::sqlite::Connection::open(&path)?;
+		ctx.execute_script("CREATE TABLE foo (id TEXT)").await?;
+		ctx.attach(AttachOptions::new(&attached), "other").await?;
+		ctx.execute_script("CREATE TABLE other.bar (id TEXT)").await
+	}.await;
+	let created = (path.exists(), attached.exists());
+
+	std::fs::remove_file(&path).ok();
+	std::fs::remove_file(&attached).ok();
+	result?;
+	assert_eq!(created, (true, true));
+	Ok(())
+}
+
+#[async_std::test]
+async fn pragma() -> sql_connect::Result<()> {
+	use std::time::Duration;
+	use sql_connect::sqlite::{
+		JournalMode,
+		Synchronous,
+		TempStore,
+		AutoVacuum
+	};
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+
+	// Must be set before anything is written to the database.
+	ctx.set_auto_vacuum(AutoVacuum::Incremental).await?;
+	assert_eq!(ctx.auto_vacuum().await?, AutoVacuum::Incremental);
+
+	assert_eq!(ctx.journal_mode().await?, JournalMode::Memory);
+	assert_eq!(ctx.set_journal_mode(JournalMode::Wal).await?, JournalMode::Memory);
+	assert_eq!(ctx.set_journal_mode(JournalMode::Off).await?, JournalMode::Off);
+
+	ctx.set_synchronous(Synchronous::Normal).await?;
+	assert_eq!(ctx.synchronous().await?, Synchronous::Normal);
+
+	ctx.set_foreign_keys(true).await?;
+	assert!(ctx.foreign_keys().await?);
+
+	ctx.set_cache_size(-4096).await?;
+	assert_eq!(ctx.cache_size().await?, -4096);
+
+	ctx.mmap_size().await?;
+	assert!(ctx.set_mmap_size(u64::MAX).await.is_err());
+
+	ctx.set_user_version(42).await?;
+	assert_eq!(ctx.user_version().await?, 42);
+	ctx.set_application_id(-7).await?;
+	assert_eq!(ctx.application_id().await?, -7);
+
+	ctx.set_temp_store(TempStore::Memory).await?;
+	assert_eq!(ctx.temp_store().await?, TempStore::Memory);
+
+	ctx.set_busy_timeout(Duration::from_millis(1500)).await?;
+	assert_eq!(ctx.busy_timeout().await?, Duration::from_millis(1500));
+	assert!(ctx.set_busy_timeout(Duration::from_secs(u64::MAX)).await.is_err());
+	Ok(())
+}
+
+#[async_std::test]
+async fn introspection() -> sql_connect::Result<()> {
+	use sql_connect::sqlite::{
+		AttachOptions,
+		IndexOrigin
+	};
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.attach(AttachOptions::memory(), "other").await?;
+	ctx.execute_script("
+		CREATE TABLE other.author (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE);
+		CREATE TABLE other.book (
+			id INTEGER PRIMARY KEY,
+			author INTEGER REFERENCES author(id) ON DELETE CASCADE,
+			title TEXT DEFAULT 'untitled'
+		);
+		CREATE INDEX other.book_title ON book (title, lower(title));
+		CREATE VIEW other.titles AS SELECT title FROM book
+	").await?;
+	let stmt = ctx.prepare("CREATE TRIGGER other.no_empty_title BEFORE INSERT ON book BEGIN SELECT RAISE(ABORT, 'empty') WHERE NEW.title = ''; END")?.unwrap();
+	ctx.execute::<()>(&stmt, vec![]).await?;
+
+	assert!(ctx.tables("main").await?.is_empty());
+
+	let tables = ctx.tables("other").await?;
+	let names: Vec<_> = tables.iter().map(|t| t.name.as_str()).collect();
+	assert_eq!(names, vec!["author", "book"]);
+
+	let author = &tables[0];
+	assert_eq!(author.columns.len(), 2);
+	assert_eq!(author.columns[0].primary_key, 1);
+	assert_eq!(author.columns[1].declared_type.as_deref(), Some("TEXT"));
+	assert!(author.columns[1].not_null);
+	assert_eq!(author.indexes.len(), 1);
+	assert!(author.indexes[0].unique);
+	assert_eq!(author.indexes[0].origin, IndexOrigin::Unique);
+	assert_eq!(author.indexes[0].columns, vec![Some("name".to_string())]);
+
+	let book = ctx.table("other", "book").await?.unwrap();
+	assert_eq!(book.columns[2].default.as_deref(), Some("'untitled'"));
+	assert_eq!(book.indexes.len(), 1);
+	assert_eq!(book.indexes[0].origin, IndexOrigin::CreateIndex);
+	assert_eq!(book.indexes[0].columns, vec![Some("title".to_string()), None]);
+	assert_eq!(book.foreign_keys.len(), 1);
+	assert_eq!(book.foreign_keys[0].table, "author");
+	assert_eq!(book.foreign_keys[0].from, vec!["author".to_string()]);
+	assert_eq!(book.foreign_keys[0].to, vec![Some("id".to_string())]);
+	assert_eq!(book.foreign_keys[0].on_delete, "CASCADE");
+	assert!(ctx.table("other", "unknown").await?.is_none());
+
+	let views = ctx.views("other").await?;
+	assert_eq!(views.len(), 1);
+	assert_eq!(views[0].name, "titles");
+
+	let triggers = ctx.triggers("other").await?;
+	assert_eq!(triggers.len(), 1);
+	assert_eq!(triggers[0].name, "no_empty_title");
+	assert_eq!(triggers[0].table, "book");
+	Ok(())
+}
+
+#[async_std::test]
+async fn trace() -> sql_connect::Result<()> {
+	use std::sync::{
+		Arc,
+		Mutex
+	};
+	use std::time::Duration;
+	use sql_connect::sqlite::{
+		TraceMask,
+		TraceEvent
+	};
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE foo (id TEXT PRIMARY KEY)").await?;
+
+	let events = Arc::new(Mutex::new(Vec::new()));
+	let log = events.clone();
+	ctx.trace(TraceMask::STATEMENT | TraceMask::ROW, move |event| log.lock().unwrap().push(event));
+
+	{
+		let stmt = ctx.prepare("INSERT INTO foo (id) VALUES (?)")?.unwrap();
+		ctx.execute::<()>(&stmt, vec!["bar".into()]).await?;
+		let stmt = ctx.prepare("SELECT id FROM foo")?.unwrap();
+		let rows: Vec<_> = ctx.execute::<String>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+		assert_eq!(rows.len(), 1);
+	}
+
+	assert_eq!(*events.lock().unwrap(), vec![
+		TraceEvent::Statement { sql: "INSERT INTO foo (id) VALUES ('bar')".to_string() },
+		TraceEvent::Statement { sql: "SELECT id FROM foo".to_string() },
+		TraceEvent::Row
+	]);
+
+	let slow = Arc::new(Mutex::new(Vec::new()));
+	let log = slow.clone();
+	ctx.log_slow_queries(Duration::from_secs(0), move |sql, _| log.lock().unwrap().push(sql.to_string()));
+	ctx.execute_script("INSERT INTO foo (id) VALUES ('biz')").await?;
+	assert_eq!(*slow.lock().unwrap(), vec!["INSERT INTO foo (id) VALUES ('biz')".to_string()]);
+
+	let mut stream = ctx.trace_stream(TraceMask::CLOSE);
+	std::mem::drop(ctx);
+	assert_eq!(stream.next().await, Some(TraceEvent::Close));
+	assert_eq!(stream.next().await, None);
+	Ok(())
+}
+
+#[async_std::test]
+async fn explain_query_plan() -> sql_connect::Result<()> {
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE foo (id INTEGER PRIMARY KEY, a INTEGER, b INTEGER); CREATE INDEX foo_a ON foo (a)").await?;
+
+	let plan = ctx.explain_query_plan("SELECT * FROM foo WHERE a = ?").await?;
+	assert!(plan.uses_index("foo_a"));
+	assert!(!plan.uses_full_scan());
+
+	let stmt = ctx.prepare("SELECT * FROM foo WHERE b = ?")?.unwrap();
+	let plan = ctx.explain_statement_plan(&stmt).await?;
+	assert!(!plan.uses_index("foo_a"));
+	assert!(plan.uses_full_scan());
+
+	let plan = ctx.explain_query_plan("SELECT * FROM foo WHERE a = 1 UNION SELECT * FROM foo WHERE b = 2").await?;
+	assert_eq!(plan.nodes.len(), 1);
+	assert_eq!(plan.nodes[0].children.len(), 2);
+	assert!(plan.uses_index("foo_a"));
+	assert!(plan.uses_full_scan());
+
+	let plan = ctx.explain_query_plan("SELECT a FROM foo").await?;
+	assert!(plan.uses_index("foo_a"));
+	assert!(plan.uses_full_scan());
+
+	let program = ctx.explain("SELECT a FROM foo WHERE id = 1").await?;
+	assert_eq!(program[0].addr, 0);
+	assert_eq!(program[0].opcode, "Init");
+	assert!(program.iter().any(|i| i.opcode == "ResultRow"));
+	Ok(())
+}
+
+#[async_std::test]
+async fn statement_introspection() -> sql_connect::Result<()> {
+	use sql_connect::sqlite::ExplainMode;
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE foo (id TEXT PRIMARY KEY, n INTEGER)").await?;
+
+	let stmt = ctx.prepare("INSERT INTO foo (id, n) VALUES (:id, ?)")?.unwrap();
+	assert_eq!(stmt.sql(), "INSERT INTO foo (id, n) VALUES (:id, ?)");
+	assert_eq!(stmt.parameter_count(), 2);
+	assert_eq!(stmt.parameter_names(), vec![Some(":id"), None]);
+	assert_eq!(stmt.parameter_name(2), None);
+	assert!(!stmt.is_readonly());
+	assert!(!stmt.is_explain());
+	assert_eq!(stmt.expanded_sql().unwrap(), "INSERT INTO foo (id, n) VALUES (NULL, NULL)");
+	assert_eq!(format!("{:?}", stmt), "Statement { sql: \"INSERT INTO foo (id, n) VALUES (:id, ?)\" }");
+
+	let stmt = ctx.prepare("SELECT n FROM foo")?.unwrap();
+	assert!(stmt.is_readonly());
+	assert_eq!(stmt.parameter_count(), 0);
+
+	let stmt = ctx.prepare("EXPLAIN QUERY PLAN SELECT n FROM foo")?.unwrap();
+	assert_eq!(stmt.explain_mode(), Some(ExplainMode::QueryPlan));
+	Ok(())
+}
+
+#[async_std::test]
+async fn statement_status() -> sql_connect::Result<()> {
+	use sql_connect::sqlite::StatementCounter;
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE foo (id TEXT PRIMARY KEY, n INTEGER); INSERT INTO foo VALUES ('a', 1); INSERT INTO foo VALUES ('b', 2)").await?;
+
+	let stmt = ctx.prepare("SELECT id FROM foo WHERE n > 0 ORDER BY n")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<String>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.len(), 2);
+
+	let status = stmt.status(true);
+	assert!(status.fullscan_steps > 0);
+	assert_eq!(status.sorts, 1);
+	assert_eq!(status.runs, 1);
+	assert!(status.vm_steps > 0);
+	assert!(status.memory_used > 0);
+
+	assert_eq!(stmt.counter(StatementCounter::FullscanStep, false), 0);
+	assert_eq!(stmt.counter(StatementCounter::Run, false), 0);
+	Ok(())
+}
+
+#[async_std::test]
+async fn connection_status() -> sql_connect::Result<()> {
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	let before = ctx.status(false)?;
+	ctx.execute_script("CREATE TABLE foo (id TEXT PRIMARY KEY); INSERT INTO foo (id) VALUES ('bar')").await?;
+
+	let stmt = ctx.prepare("SELECT id FROM foo")?.unwrap();
+	let after = ctx.status(false)?;
+	let diff = after - before;
+	assert!(diff.schema_used.current > 0);
+	assert!(diff.statement_used.current > 0);
+	assert!(after.cache_used.current > 0);
+	assert_eq!(after.deferred_foreign_keys.current, 0);
+
+	let rows: Vec<_> = ctx.execute::<String>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.len(), 1);
+	assert!(ctx.status(true)?.cache_hit.current > 0);
+	assert_eq!(ctx.status(false)?.cache_hit.current, 0);
+
+	let library = sql_connect::sqlite::library_status(false)?;
+	assert!(library.memory_used.current > 0);
+	assert!(library.memory_used.highwater >= library.memory_used.current);
+	Ok(())
+}
+
+fn database_error(e: &sql_connect::Error) -> &sql_connect::sqlite::DatabaseError {
+	e.get_ref().and_then(|source| source.downcast_ref()).expect("not a database error")
+}
+
+#[async_std::test]
+async fn error_details() -> sql_connect::Result<()> {
+	use sql_connect::sqlite::{
+		Constraint,
+		SqliteError
+	};
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("PRAGMA foreign_keys = ON").await?;
+	ctx.execute_script("CREATE TABLE foo (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE, age INTEGER CHECK (age > 0))").await?;
+	ctx.execute_script("CREATE TABLE bar (foo INTEGER REFERENCES foo (id))").await?;
+	ctx.execute_script("CREATE TABLE baz (a TEXT, b TEXT, PRIMARY KEY (a, b))").await?;
+	ctx.execute_script("INSERT INTO foo (id, name, age) VALUES (1, 'a', 1); INSERT INTO baz (a, b) VALUES ('a', 'b')").await?;
+
+	let e = ctx.prepare("SELECT id FROM foo WHERE unknown = 1").unwrap_err();
+	let details = database_error(&e);
+	assert_eq!(details.code(), SqliteError::Unknown);
+	assert_eq!(details.message(), "no such column: unknown");
+	assert_eq!(details.sql(), Some("SELECT id FROM foo WHERE unknown = 1"));
+	assert_eq!(details.offset(), Some(25));
+	assert!(e.to_string().contains("no such column: unknown"));
+	assert!(std::error::Error::source(&e).unwrap().to_string().contains("no such column: unknown"));
+
+	let e = ctx.prepare("SELECT * FORM foo").unwrap_err();
+	assert_eq!(database_error(&e).offset(), Some(9));
+
+	let violations = [
+		("INSERT INTO foo (id, name, age) VALUES (2, 'a', 1)", Constraint::Unique),
+		("INSERT INTO foo (id, name, age) VALUES (2, NULL, 1)", Constraint::NotNull),
+		("INSERT INTO foo (id, name, age) VALUES (2, 'b', 0)", Constraint::Check),
+		("INSERT INTO bar (foo) VALUES (2)", Constraint::ForeignKey),
+		("INSERT INTO baz (a, b) VALUES ('a', 'b')", Constraint::PrimaryKey)
+	];
+
+	for (sql, constraint) in violations.iter() {
+		let stmt = ctx.prepare(sql)?.unwrap();
+		let e = ctx.execute::<()>(&stmt, vec![]).await.err().expect("constraint violation");
+		assert!(matches!(e.kind(), sql_connect::ErrorKind::ConstraintViolation));
+
+		let details = database_error(&e);
+		assert_eq!(details.code(), SqliteError::Constraint);
+		assert_eq!(details.constraint(), Some(*constraint));
+		assert_eq!(details.sql(), Some(*sql));
+		assert!(details.message().contains("constraint failed"));
+	}
+
+	let e = sql_connect::sqlite::Connection::open("/nonexistent/dir/db.sqlite").err().expect("cannot open");
+	assert_eq!(database_error(&e).code(), SqliteError::CantOpen);
+	Ok(())
+}
+
+#[test]
+fn error_kind_mapping() {
+	use libsqlite3_sys as ffi;
+	use sql_connect::{
+		ErrorKind,
+		sqlite::SqliteError
+	};
+
+	let codes = [
+		(ffi::SQLITE_ERROR, SqliteError::Unknown, ErrorKind::Failure),
+		(ffi::SQLITE_INTERNAL, SqliteError::Internal, ErrorKind::Internal),
+		(ffi::SQLITE_PERM, SqliteError::Perm, ErrorKind::PermissionDenied),
+		(ffi::SQLITE_ABORT, SqliteError::Abort, ErrorKind::Aborted),
+		(ffi::SQLITE_BUSY, SqliteError::Busy, ErrorKind::Busy),
+		(ffi::SQLITE_LOCKED, SqliteError::Locked, ErrorKind::Locked),
+		(ffi::SQLITE_NOMEM, SqliteError::NoMem, ErrorKind::OutOfMemory),
+		(ffi::SQLITE_READONLY, SqliteError::ReadOnly, ErrorKind::ReadOnly),
+		(ffi::SQLITE_INTERRUPT, SqliteError::Interrupt, ErrorKind::Interrupted),
+		(ffi::SQLITE_IOERR, SqliteError::IO, ErrorKind::Io),
+		(ffi::SQLITE_CORRUPT, SqliteError::Corrupt, ErrorKind::Corrupt),
+		(ffi::SQLITE_NOTFOUND, SqliteError::NotFound, ErrorKind::Failure),
+		(ffi::SQLITE_FULL, SqliteError::Full, ErrorKind::DiskFull),
+		(ffi::SQLITE_CANTOPEN, SqliteError::CantOpen, ErrorKind::CantOpen),
+		(ffi::SQLITE_PROTOCOL, SqliteError::Protocol, ErrorKind::Failure),
+		(ffi::SQLITE_EMPTY, SqliteError::Empty, ErrorKind::Failure),
+		(ffi::SQLITE_SCHEMA, SqliteError::Schema, ErrorKind::SchemaChanged),
+		(ffi::SQLITE_TOOBIG, SqliteError::TooBig, ErrorKind::TooBig),
+		(ffi::SQLITE_CONSTRAINT, SqliteError::Constraint, ErrorKind::ConstraintViolation),
+		(ffi::SQLITE_MISMATCH, SqliteError::Mismatch, ErrorKind::TypeMismatch),
+		(ffi::SQLITE_MISUSE, SqliteError::Misuse, ErrorKind::Misuse),
+		(ffi::SQLITE_NOLFS, SqliteError::NoLFS, ErrorKind::Io),
+		(ffi::SQLITE_AUTH, SqliteError::Auth, ErrorKind::Unauthorized),
+		(ffi::SQLITE_FORMAT, SqliteError::Format, ErrorKind::Failure),
+		(ffi::SQLITE_RANGE, SqliteError::Range, ErrorKind::OutOfRange),
+		(ffi::SQLITE_NOTADB, SqliteError::NotADB, ErrorKind::NotADatabase)
+	];
+
+	for (code, error, kind) in codes.iter() {
+		assert_eq!(SqliteError::from_code(*code), *error);
+		assert_eq!(error.code(), *code);
+		assert_eq!(error.kind(), *kind);
+
+		let e: sql_connect::Error = (*error).into();
+		assert_eq!(*e.kind(), *kind);
+		assert_eq!(e.kind().is_busy(), *code == ffi::SQLITE_BUSY);
+	}
+
+	// Extended result codes map to their primary code.
+	assert_eq!(SqliteError::from_code(ffi::SQLITE_BUSY_SNAPSHOT), SqliteError::Busy);
+	assert_eq!(SqliteError::from_code(ffi::SQLITE_LOCKED_SHAREDCACHE), SqliteError::Locked);
+	assert_eq!(SqliteError::from_code(ffi::SQLITE_IOERR_READ), SqliteError::IO);
+}
+
+#[async_std::test]
+async fn error_kinds() -> sql_connect::Result<()> {
+	use std::error::Error;
+	use sql_connect::ErrorKind;
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE foo (id INTEGER PRIMARY KEY); INSERT INTO foo (id) VALUES (1)").await?;
+
+	let stmt = ctx.prepare("INSERT INTO foo (id) VALUES ('bar')")?.unwrap();
+	let e = ctx.execute::<()>(&stmt, vec![]).await.err().expect("type mismatch");
+	assert_eq!(*e.kind(), ErrorKind::TypeMismatch);
+	assert_eq!(e.kind().to_string(), "type mismatch");
+	assert_eq!(e.to_string(), "type mismatch: datatype mismatch in `INSERT INTO foo (id) VALUES ('bar')`");
+	assert_eq!(e.source().unwrap().to_string(), "datatype mismatch in `INSERT INTO foo (id) VALUES ('bar')`");
+
+	// Table locks of connections sharing the same cache.
+	let mut shared = sql_connect::sqlite::Connection::open_uri("file:error_kinds?mode=memory&cache=shared")?;
+	let mut other = sql_connect::sqlite::Connection::open_uri("file:error_kinds?mode=memory&cache=shared")?;
+	shared.execute_script("CREATE TABLE foo (id INTEGER); BEGIN; INSERT INTO foo (id) VALUES (1)").await?;
+	let e = other.execute_script("SELECT id FROM foo").await.expect_err("locked");
+	assert!(e.kind().is_locked());
+	assert!(!e.kind().is_busy());
+
+	let bytes = ctx.serialize("main")?;
+	let mut copy = sql_connect::sqlite::Connection::new()?;
+	copy.deserialize("main", &bytes, true)?;
+	let e = copy.execute_script("INSERT INTO foo (id) VALUES (2)").await.expect_err("read-only");
+	assert_eq!(*e.kind(), ErrorKind::ReadOnly);
+
+	let mut garbage = sql_connect::sqlite::Connection::new()?;
+	garbage.deserialize("main", &[0x42; 1024], true)?;
+	let e = garbage.prepare("SELECT * FROM sqlite_master").expect_err("not a database");
+	assert_eq!(*e.kind(), ErrorKind::NotADatabase);
+
+	let e = sql_connect::sqlite::Connection::open("/nonexistent/dir/db.sqlite").err().expect("cannot open");
+	assert_eq!(*e.kind(), ErrorKind::CantOpen);
+	assert!(e.source().is_some());
+	Ok(())
+}
+
+/// Store the given value in a table and read it back.
+async fn round_trip<'a, V: Into<sql_connect::Value<'a>>, T: sql_connect::FromValue>(ctx: &mut sql_connect::sqlite::Connection, value: V) -> sql_connect::Result<T> {
+	ctx.execute_script("DELETE FROM value").await?;
+	let stmt = ctx.prepare("INSERT INTO value (v) VALUES (?)")?.unwrap();
+	ctx.execute::<()>(&stmt, vec![value.into()]).await?;
+
+	let stmt = ctx.prepare("SELECT v FROM value")?.unwrap();
+	let mut rows: Vec<_> = ctx.execute::<T>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.len(), 1);
+	rows.pop().unwrap()
+}
+
+#[async_std::test]
+async fn value_conversions() -> sql_connect::Result<()> {
+	use std::borrow::Cow;
+	use std::rc::Rc;
+	use std::sync::Arc;
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE value (v)").await?;
+
+	assert!(round_trip::<_, bool>(&mut ctx, true).await?);
+	assert!(!round_trip::<_, bool>(&mut ctx, false).await?);
+	assert_eq!(round_trip::<_, i8>(&mut ctx, -128i8).await?, -128);
+	assert_eq!(round_trip::<_, i16>(&mut ctx, i16::MIN).await?, i16::MIN);
+	assert_eq!(round_trip::<_, u8>(&mut ctx, 255u8).await?, 255);
+	assert_eq!(round_trip::<_, u16>(&mut ctx, u16::MAX).await?, u16::MAX);
+	assert_eq!(round_trip::<_, isize>(&mut ctx, -42isize).await?, -42);
+	assert_eq!(round_trip::<_, i32>(&mut ctx, i32::MIN).await?, i32::MIN);
+	assert_eq!(round_trip::<_, i64>(&mut ctx, i64::MAX).await?, i64::MAX);
+	assert_eq!(round_trip::<_, u32>(&mut ctx, u32::MAX).await?, u32::MAX);
+
+	assert_eq!(round_trip::<_, f32>(&mut ctx, 1.5f32).await?, 1.5);
+	assert_eq!(round_trip::<_, f64>(&mut ctx, -0.1f64).await?, -0.1);
+	assert_eq!(round_trip::<_, f64>(&mut ctx, 3i64).await?, 3.0);
+
+	let text = "héllo".to_string();
+	assert_eq!(round_trip::<_, String>(&mut ctx, &text).await?, text);
+	assert_eq!(&*round_trip::<_, Box<str>>(&mut ctx, Box::<str>::from("box")).await?, "box");
+	assert_eq!(&*round_trip::<_, Rc<str>>(&mut ctx, Rc::<str>::from("rc")).await?, "rc");
+	assert_eq!(&*round_trip::<_, Arc<str>>(&mut ctx, Arc::<str>::from("arc")).await?, "arc");
+	assert_eq!(round_trip::<_, Cow<str>>(&mut ctx, Cow::Borrowed("cow")).await?, "cow");
+	assert_eq!(round_trip::<_, Cow<str>>(&mut ctx, Cow::<str>::Owned("cow".to_string())).await?, "cow");
+	assert_eq!(round_trip::<_, String>(&mut ctx, "").await?, "");
+
+	let bytes = vec![0u8, 1, 2, 255];
+	assert_eq!(round_trip::<_, Vec<u8>>(&mut ctx, bytes.clone()).await?, bytes);
+	assert_eq!(round_trip::<_, Vec<u8>>(&mut ctx, &bytes).await?, bytes);
+	assert_eq!(round_trip::<_, Vec<u8>>(&mut ctx, &bytes[1..]).await?, &bytes[1..]);
+	assert_eq!(&*round_trip::<_, Box<[u8]>>(&mut ctx, bytes.clone().into_boxed_slice()).await?, &bytes[..]);
+	assert_eq!(&*round_trip::<_, Rc<[u8]>>(&mut ctx, Rc::<[u8]>::from(&bytes[..])).await?, &bytes[..]);
+	assert_eq!(&*round_trip::<_, Arc<[u8]>>(&mut ctx, Arc::<[u8]>::from(&bytes[..])).await?, &bytes[..]);
+	assert_eq!(round_trip::<_, Cow<[u8]>>(&mut ctx, Cow::Borrowed(&bytes[..])).await?, &bytes[..]);
+	assert_eq!(round_trip::<_, Vec<u8>>(&mut ctx, Vec::new()).await?, Vec::<u8>::new());
+
+	assert_eq!(round_trip::<_, Option<i64>>(&mut ctx, Some(7i64)).await?, Some(7));
+	assert_eq!(round_trip::<_, Option<i64>>(&mut ctx, None::<i64>).await?, None);
+	assert_eq!(round_trip::<_, Option<String>>(&mut ctx, Some("some")).await?.as_deref(), Some("some"));
+	assert_eq!(round_trip::<_, Option<Vec<u8>>>(&mut ctx, None::<Vec<u8>>).await?, None);
+	assert_eq!(round_trip::<_, Option<bool>>(&mut ctx, Some(true)).await?, Some(true));
+	Ok(())
+}
+
+#[async_std::test]
+async fn owned_value() -> sql_connect::Result<()> {
+	use std::collections::HashSet;
+	use sql_connect::{
+		Value,
+		OwnedValue
+	};
+
+	let values: Vec<OwnedValue> = vec![
+		Value::from("b"),
+		Value::from(vec![1u8, 2]),
+		Value::from(2.5),
+		Value::Null,
+		Value::from(3i64),
+		Value::from("a"),
+		Value::from(-1.0),
+		Value::from(vec![1u8]),
+		Value::from(2i64),
+		Value::from(i64::MAX),
+		Value::from(9223372036854775808.0)
+	].into_iter().map(Value::into_owned).collect();
+
+	// Sort order must match the one of SQLite.
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE value (v)").await?;
+	for value in &values {
+		let stmt = ctx.prepare("INSERT INTO value (v) VALUES (?)")?.unwrap();
+		ctx.execute::<()>(&stmt, vec![value.clone()]).await?;
+	}
+
+	let stmt = ctx.prepare("SELECT v FROM value ORDER BY v")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<OwnedValue>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	let expected = rows.into_iter().collect::<sql_connect::Result<Vec<_>>>()?;
+
+	let mut sorted = values.clone();
+	sorted.sort();
+	assert_eq!(sorted, expected);
+	assert_eq!(format!("{:?}", &sorted[..3]), "[Null, Float(-1.0), Integer(2)]");
+
+	// Numeric values compare by value, across types.
+	assert_eq!(Value::from(2i64), Value::from(2.0));
+	assert!(Value::from(2i64) < Value::from(2.5));
+	assert!(Value::from(i64::MAX) < Value::from(9223372036854775808.0));
+	assert!(Value::from(1000i64) < Value::from("1"));
+	assert!(Value::from("z") < Value::from(vec![0u8]));
+	assert_eq!(Value::Float(f64::NAN), Value::Null);
+
+	// Borrowed and owned values are equal and hash the same way.
+	let text = "foo".to_string();
+	let borrowed = Value::from(&text);
+	let owned = borrowed.clone().into_owned();
+	assert_eq!(borrowed, owned);
+
+	let set: HashSet<OwnedValue> = vec![Value::from(1i64), Value::from(1.0), owned, Value::Null, Value::Float(f64::NAN)].into_iter().collect();
+	assert_eq!(set.len(), 3);
+	assert!(set.contains(&Value::from("foo")));
+	Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[async_std::test]
+async fn serde_rows_and_parameters() -> sql_connect::Result<()> {
+	use std::collections::BTreeMap;
+	use serde::{
+		Serialize,
+		Deserialize
+	};
+	use sql_connect::Deserialized;
+
+	#[derive(Serialize, Deserialize, Debug, PartialEq)]
+	enum Status {
+		Active,
+		Banned { reason: String }
+	}
+
+	#[derive(Serialize, Deserialize, Debug, PartialEq)]
+	struct Address {
+		city: String,
+		zip: u32
+	}
+
+	#[derive(Serialize, Deserialize, Debug, PartialEq)]
+	struct User {
+		id: i64,
+		name: String,
+		admin: bool,
+		score: Option<f64>,
+		status: Status,
+		tags: Vec<String>,
+		address: Address
+	}
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE user (id INTEGER PRIMARY KEY, name TEXT, admin INTEGER, score REAL, status TEXT, tags TEXT, address TEXT)").await?;
+
+	let users = vec![
+		User {
+			id: 1,
+			name: "alice".to_string(),
+			admin: true,
+			score: Some(1.5),
+			status: Status::Active,
+			tags: vec!["a".to_string(), "b".to_string()],
+			address: Address { city: "Rennes".to_string(), zip: 35000 }
+		},
+		User {
+			id: 2,
+			name: "bob".to_string(),
+			admin: false,
+			score: None,
+			status: Status::Banned { reason: "spam".to_string() },
+			tags: Vec::new(),
+			address: Address { city: "Paris".to_string(), zip: 75000 }
+		}
+	];
+
+	let insert = ctx.prepare("INSERT INTO user VALUES (:id, :name, @admin, $score, :status, :tags, :address)")?.unwrap();
+	for user in &users {
+		let args = insert.named_arguments(user)?;
+		ctx.execute::<()>(&insert, args).await?;
+	}
+
+	// Nested values are stored as JSON text.
+	let stmt = ctx.prepare("SELECT status, tags, address FROM user WHERE id = 2")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<(String, String, String)>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.into_iter().next().unwrap()?, (
+		r#"{"Banned":{"reason":"spam"}}"#.to_string(),
+		"[]".to_string(),
+		r#"{"city":"Paris","zip":75000}"#.to_string()
+	));
+
+	let stmt = ctx.prepare("SELECT * FROM user ORDER BY id")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<Deserialized<User>>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	let loaded: Vec<User> = rows.into_iter().map(|row| row.map(Deserialized::into_inner)).collect::<sql_connect::Result<_>>()?;
+	assert_eq!(loaded, users);
+
+	// Maps, tuples and single values.
+	let stmt = ctx.prepare("SELECT name, status FROM user WHERE id = :id")?.unwrap();
+	let mut params = BTreeMap::new();
+	params.insert("id", 1);
+	let args = stmt.named_arguments(&params)?;
+	let rows: Vec<_> = ctx.execute::<Deserialized<BTreeMap<String, String>>>(&stmt, args).await?.unwrap_rows().collect().await;
+	let map = rows.into_iter().next().unwrap()?.into_inner();
+	assert_eq!(map.get("name").map(String::as_str), Some("alice"));
+	assert_eq!(map.get("status").map(String::as_str), Some("Active"));
+
+	let stmt = ctx.prepare("SELECT id, name FROM user ORDER BY id")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<Deserialized<(i64, String)>>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.into_iter().next().unwrap()?.0, (1, "alice".to_string()));
+
+	let stmt = ctx.prepare("SELECT COUNT(*) FROM user")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<Deserialized<u32>>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(*rows.into_iter().next().unwrap()?, 2);
+
+	// Deserialization errors are yielded by the row stream.
+	let stmt = ctx.prepare("SELECT NULL AS id, name FROM user")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<Deserialized<(i64, String)>>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert!(matches!(rows[0].as_ref().unwrap_err().kind(), sql_connect::ErrorKind::Conversion(_)));
+
+	// Missing parameters are reported.
+	let stmt = ctx.prepare("SELECT * FROM user WHERE name = :name AND id = :id")?.unwrap();
+	let e = stmt.named_arguments(&params).unwrap_err();
+	assert_eq!(*e.kind(), sql_connect::ErrorKind::InvalidArgument(":name".to_string()));
+	Ok(())
+}
+
+#[cfg(feature = "json")]
+#[async_std::test]
+async fn json_values() -> sql_connect::Result<()> {
+	use serde::{
+		Serialize,
+		Deserialize
+	};
+	use sql_connect::{
+		Json,
+		Jsonb,
+		ErrorKind,
+		json::{
+			encode_jsonb,
+			decode_jsonb
+		}
+	};
+
+	#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+	struct Settings {
+		theme: String,
+		size: u32,
+		ratio: f64,
+		tags: Vec<String>,
+		parent: Option<Box<Settings>>
+	}
+
+	let settings = Settings {
+		theme: "dark \"night\"".to_string(),
+		size: 12,
+		ratio: 0.5,
+		tags: vec!["a".to_string(), "a long tag exceeding eleven bytes".repeat(10)],
+		parent: Some(Box::new(Settings {
+			theme: "light".to_string(),
+			size: 300,
+			ratio: -1e100,
+			tags: Vec::new(),
+			parent: None
+		}))
+	};
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE config (id INTEGER PRIMARY KEY, value)").await?;
+	let insert = ctx.prepare("INSERT INTO config VALUES (?, ?)")?.unwrap();
+	ctx.execute::<()>(&insert, vec![1.into(), Json(settings.clone()).to_value()?]).await?;
+	ctx.execute::<()>(&insert, vec![2.into(), Jsonb(settings.clone()).into()]).await?;
+
+	// Maps with non-string keys cannot be serialized.
+	let pairs: std::collections::BTreeMap<_, _> = vec![((1, 2), 3)].into_iter().collect();
+	assert!(matches!(Json(&pairs).to_value().unwrap_err().kind(), ErrorKind::Conversion(_)));
+	assert!(matches!(Jsonb(&pairs).to_value().unwrap_err().kind(), ErrorKind::Conversion(_)));
+	ctx.execute::<()>(&insert, vec![3.into(), serde_json::json!({ "size": 12 }).into()]).await?;
+
+	// JSON text can be used by SQL functions.
+	let stmt = ctx.prepare("SELECT json_extract(value, '$.parent.size'), typeof(value) FROM config WHERE id = 1")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<(i64, String)>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.into_iter().next().unwrap()?, (300, "text".to_string()));
+
+	// JSONB falls back to JSON text if unsupported.
+	let stmt = ctx.prepare("SELECT json_extract(value, '$.parent.size'), typeof(value) FROM config WHERE id = 2")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<(i64, String)>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	let stored = if sql_connect::sqlite::supports_jsonb() { "blob" } else { "text" };
+	assert_eq!(rows.into_iter().next().unwrap()?, (300, stored.to_string()));
+
+	// Both wrappers accept both formats.
+	let stmt = ctx.prepare("SELECT value FROM config WHERE id <= 2 ORDER BY id")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<Json<Settings>>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	for row in rows {
+		assert_eq!(row?.into_inner(), settings);
+	}
+
+	let rows: Vec<_> = ctx.execute::<Jsonb<Settings>>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	for row in rows {
+		assert_eq!(row?.into_inner(), settings);
+	}
+
+	let stmt = ctx.prepare("SELECT value FROM config WHERE id = 3")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<serde_json::Value>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.into_iter().next().unwrap()?, serde_json::json!({ "size": 12 }));
+
+	// Extracted values and `NULL`.
+	let stmt = ctx.prepare("SELECT json_extract(value, '$.size'), json_extract(value, '$.ratio'), json_extract(value, '$.theme'), json_extract(value, '$.parent') FROM config WHERE id = 1 UNION ALL SELECT NULL, NULL, NULL, NULL")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<(serde_json::Value, serde_json::Value, serde_json::Value, serde_json::Value)>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	let mut rows = rows.into_iter();
+	let (size, ratio, theme, parent) = rows.next().unwrap()?;
+	assert_eq!(size, serde_json::json!(12));
+	assert_eq!(ratio, serde_json::json!(0.5));
+	assert_eq!(theme, serde_json::json!("dark \"night\""));
+	assert_eq!(parent["size"], serde_json::json!(300));
+	let (size, ratio, theme, parent) = rows.next().unwrap()?;
+	assert!(size.is_null() && ratio.is_null() && theme.is_null() && parent.is_null());
+
+	// JSONB produced by SQLite (`jsonb('{"a":[1,2.5,"x\\"",null,true]}')`).
+	let bytes = b"\xcc\x10\x17a\xcb\x0c\x131\x352.5\x38x\\\"\x00\x01";
+	assert_eq!(decode_jsonb(bytes)?, serde_json::json!({ "a": [1, 2.5, "x\"", null, true] }));
+
+	// JSON5 elements.
+	assert_eq!(decode_jsonb(b"\x44\x30x1F")?, serde_json::json!(31));
+	assert_eq!(decode_jsonb(b"\x26.5")?, serde_json::json!(0.5));
+	assert_eq!(decode_jsonb(b"\x99\\x41\\'\\\nb")?, serde_json::json!("A'b"));
+	assert!(decode_jsonb(b"\x23\x31").is_err());
+	assert!(decode_jsonb(b"\x0d").is_err());
+
+	// Deeply nested arrays are rejected.
+	let mut nested = vec![0x00];
+	for _ in 0..1000 {
+		let mut array = Vec::new();
+		array.push(0xeb);
+		array.extend_from_slice(&(nested.len() as u32).to_be_bytes());
+		array.extend_from_slice(&nested);
+		nested = array;
+	}
+	assert!(decode_jsonb(&nested).is_err());
+	assert!(decode_jsonb(&nested[(nested.len() - 1 - 128 * 5)..]).is_ok());
+
+	let value = serde_json::to_value(&settings).unwrap();
+	assert_eq!(decode_jsonb(&encode_jsonb(&value))?, value);
+
+	if sql_connect::sqlite::supports_jsonb() {
+		let stmt = ctx.prepare("SELECT json(value) FROM config WHERE id = 2")?.unwrap();
+		let rows: Vec<_> = ctx.execute::<serde_json::Value>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+		assert_eq!(rows.into_iter().next().unwrap()?, value);
+	}
+
+	Ok(())
+}
+
+async fn stored_type(ctx: &mut sql_connect::sqlite::Connection) -> sql_connect::Result<String> {
+	let stmt = ctx.prepare("SELECT typeof(v) FROM value")?.unwrap();
+	let mut rows: Vec<_> = ctx.execute::<String>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	rows.pop().unwrap()
+}
+
+#[async_std::test]
+async fn ip_addresses() -> sql_connect::Result<()> {
+	use std::net::{
+		IpAddr,
+		Ipv4Addr,
+		Ipv6Addr
+	};
+	use sql_connect::AsBlob;
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE value (v)").await?;
+
+	let v4 = Ipv4Addr::new(192, 168, 0, 1);
+	let v6: Ipv6Addr = "2001:db8::1".parse().unwrap();
+
+	assert_eq!(round_trip::<_, String>(&mut ctx, IpAddr::V4(v4)).await?, "192.168.0.1");
+	assert_eq!(round_trip::<_, IpAddr>(&mut ctx, IpAddr::V6(v6)).await?, IpAddr::V6(v6));
+	assert_eq!(stored_type(&mut ctx).await?, "text");
+	assert_eq!(round_trip::<_, Ipv4Addr>(&mut ctx, v4).await?, v4);
+
+	assert_eq!(round_trip::<_, Vec<u8>>(&mut ctx, AsBlob(v4)).await?, vec![192, 168, 0, 1]);
+	assert_eq!(round_trip::<_, IpAddr>(&mut ctx, AsBlob(IpAddr::V6(v6))).await?, IpAddr::V6(v6));
+	assert_eq!(stored_type(&mut ctx).await?, "blob");
+	assert_eq!(round_trip::<_, AsBlob<Ipv6Addr>>(&mut ctx, AsBlob(v6)).await?.into_inner(), v6);
+	Ok(())
+}
+
+#[cfg(feature = "uuid")]
+#[async_std::test]
+async fn uuid_values() -> sql_connect::Result<()> {
+	use uuid::Uuid;
+	use sql_connect::AsText;
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE value (v)").await?;
+
+	let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+	assert_eq!(round_trip::<_, Uuid>(&mut ctx, uuid).await?, uuid);
+	assert_eq!(stored_type(&mut ctx).await?, "blob");
+	assert_eq!(round_trip::<_, Vec<u8>>(&mut ctx, uuid).await?, uuid.as_bytes().to_vec());
+
+	assert_eq!(round_trip::<_, String>(&mut ctx, AsText(uuid)).await?, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+	assert_eq!(round_trip::<_, Uuid>(&mut ctx, AsText(uuid)).await?, uuid);
+	assert_eq!(round_trip::<_, Uuid>(&mut ctx, "67E5504410B1426F9247BB680E5FE0C8").await?, uuid);
+	Ok(())
+}
+
+#[cfg(feature = "rust_decimal")]
+#[async_std::test]
+async fn decimal_values() -> sql_connect::Result<()> {
+	use rust_decimal::Decimal;
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE value (v)").await?;
+
+	let d: Decimal = "12345678901234567890.123456789".parse().unwrap();
+	assert_eq!(round_trip::<_, Decimal>(&mut ctx, d).await?, d);
+	assert_eq!(stored_type(&mut ctx).await?, "text");
+	assert_eq!(round_trip::<_, Decimal>(&mut ctx, 42).await?, Decimal::new(42, 0));
+	assert_eq!(round_trip::<_, Decimal>(&mut ctx, 0.25).await?, Decimal::new(25, 2));
+	Ok(())
+}
+
+#[cfg(feature = "ipnet")]
+#[async_std::test]
+async fn ip_networks() -> sql_connect::Result<()> {
+	use ipnet::{
+		IpNet,
+		Ipv4Net
+	};
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE value (v)").await?;
+
+	let net: IpNet = "10.1.0.0/16".parse().unwrap();
+	assert_eq!(round_trip::<_, String>(&mut ctx, net).await?, "10.1.0.0/16");
+	assert_eq!(round_trip::<_, IpNet>(&mut ctx, net).await?, net);
+	assert_eq!(round_trip::<_, Ipv4Net>(&mut ctx, "192.168.0.0/24").await?, "192.168.0.0/24".parse().unwrap());
+	Ok(())
+}
+
+#[cfg(feature = "url")]
+#[async_std::test]
+async fn url_values() -> sql_connect::Result<()> {
+	use url::Url;
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE value (v)").await?;
+
+	let url = Url::parse("https://example.com/a b?q=1").unwrap();
+	assert_eq!(round_trip::<_, String>(&mut ctx, url.clone()).await?, "https://example.com/a%20b?q=1");
+	assert_eq!(round_trip::<_, Url>(&mut ctx, url.clone()).await?, url);
+	Ok(())
+}
+
+#[async_std::test]
+async fn dynamic_rows() -> sql_connect::Result<()> {
+	use sql_connect::{
+		Row,
+		Value,
+		ErrorKind
+	};
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE user (id INTEGER PRIMARY KEY, name TEXT, score REAL); INSERT INTO user VALUES (1, 'alice', 1.5), (2, 'bob', NULL)").await?;
+
+	let stmt = ctx.prepare("SELECT id, name, score AS points FROM user ORDER BY id")?.unwrap();
+	let rows: Vec<_> = ctx.execute::<Row>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	let rows = rows.into_iter().collect::<sql_connect::Result<Vec<_>>>()?;
+	assert_eq!(rows.len(), 2);
+
+	let row = &rows[0];
+	assert_eq!(row.column_count(), 3);
+	assert_eq!(row.column_name(2), Some("points"));
+	assert_eq!(row.column_name(3), None);
+	assert_eq!(row.column_index("name"), Some(1));
+	assert_eq!(row.get::<i64, _>(0), 1);
+	assert_eq!(row.get::<String, _>("name"), "alice");
+	assert_eq!(row.get::<f64, _>("points"), 1.5);
+	assert_eq!(row.value(1), Some(Value::from("alice")));
+	assert_eq!(row.values().len(), 3);
+
+	assert_eq!(rows[1].get::<Option<f64>, _>("points"), None);
+	assert_eq!(rows[1].try_get::<String, _>(&"name".to_string())?, "bob");
+
+	let err = row.try_get::<i64, _>("score").unwrap_err();
+	assert_eq!(err.kind(), &ErrorKind::InvalidArgument("no column `score`".to_string()));
+	let err = row.try_get::<i64, _>(3).unwrap_err();
+	assert_eq!(err.kind(), &ErrorKind::InvalidArgument("no column at index 3".to_string()));
+
+	// Values of the wrong type.
+	assert_eq!(row.try_get::<i64, _>("name").unwrap_err().kind(), &ErrorKind::TypeMismatch);
+	assert_eq!(rows[1].try_get::<f64, _>("points").unwrap_err().kind(), &ErrorKind::TypeMismatch);
+	assert_eq!(row.try_get::<Option<String>, _>(0).unwrap_err().kind(), &ErrorKind::TypeMismatch);
+	assert_eq!(row.try_get::<u8, _>(0)?, 1);
+	let big = ctx.fetch_one::<Row>("SELECT 1099511627776 AS big", vec![]).await?;
+	assert_eq!(big.try_get::<i32, _>("big").unwrap_err().kind(), &ErrorKind::TypeMismatch);
+	assert_eq!(big.try_get::<u32, _>("big").unwrap_err().kind(), &ErrorKind::TypeMismatch);
+	assert_eq!(big.try_get::<i64, _>("big")?, 1 << 40);
+	Ok(())
+}
+
+#[async_std::test]
+async fn dynamic_row_collections() -> sql_connect::Result<()> {
+	use std::collections::BTreeMap;
+	use sql_connect::{
+		Value,
+		OwnedValue
+	};
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE item (id INTEGER PRIMARY KEY, name TEXT, data BLOB); INSERT INTO item VALUES (1, 'a', x'0102')").await?;
+	let stmt = ctx.prepare("SELECT name, id, data, NULL AS id FROM item")?.unwrap();
+
+	let rows: Vec<_> = ctx.execute::<Vec<OwnedValue>>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	assert_eq!(rows.into_iter().next().unwrap()?, vec![Value::from("a"), Value::from(1), Value::from(vec![1u8, 2]), Value::Null]);
+
+	let rows: Vec<_> = ctx.execute::<BTreeMap<String, OwnedValue>>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+	let map = rows.into_iter().next().unwrap()?;
+	assert_eq!(map.keys().collect::<Vec<_>>(), vec!["data", "id", "name"]);
+	assert_eq!(map["id"], Value::from(1));
+
+	#[cfg(feature = "indexmap")]
+	{
+		let rows: Vec<_> = ctx.execute::<indexmap::IndexMap<String, OwnedValue>>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+		let map = rows.into_iter().next().unwrap()?;
+		assert_eq!(map.keys().collect::<Vec<_>>(), vec!["name", "id", "data"]);
+		assert_eq!(map["id"], Value::from(1));
+	}
+
+	#[cfg(feature = "json")]
+	{
+		let rows: Vec<_> = ctx.execute::<serde_json::Map<String, serde_json::Value>>(&stmt, vec![]).await?.unwrap_rows().collect().await;
+		let map = rows.into_iter().next().unwrap()?;
+		assert_eq!(serde_json::Value::Object(map), serde_json::json!({ "name": "a", "id": 1, "data": [1, 2] }));
+	}
+
+	Ok(())
+}
+
+#[async_std::test]
+async fn fetch_helpers() -> sql_connect::Result<()> {
+	use sql_connect::{
+		Row,
+		ErrorKind
+	};
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE user (id INTEGER PRIMARY KEY, name TEXT); INSERT INTO user VALUES (1, 'alice'), (2, 'bob')").await?;
+
+	let all: Vec<(i64, String)> = ctx.fetch_all("SELECT id, name FROM user ORDER BY id", vec![]).await?;
+	assert_eq!(all, vec![(1, "alice".to_string()), (2, "bob".to_string())]);
+	assert!(ctx.fetch_all::<i64>("UPDATE user SET name = name", vec![]).await?.is_empty());
+
+	let name: String = ctx.fetch_one("SELECT name FROM user WHERE id = ?", vec![2.into()]).await?;
+	assert_eq!(name, "bob");
+	let err = ctx.fetch_one::<String>("SELECT name FROM user WHERE id = ?", vec![3.into()]).await.unwrap_err();
+	assert_eq!(err.kind(), &ErrorKind::RowNotFound);
+	let err = ctx.fetch_one::<String>("SELECT name FROM user", vec![]).await.unwrap_err();
+	assert_eq!(err.kind(), &ErrorKind::TooManyRows);
+
+	assert_eq!(ctx.fetch_optional::<String>("SELECT name FROM user WHERE id = 1", vec![]).await?, Some("alice".to_string()));
+	assert_eq!(ctx.fetch_optional::<String>("SELECT name FROM user WHERE id = 3", vec![]).await?, None);
+	assert_eq!(ctx.fetch_optional::<String>("SELECT name FROM user", vec![]).await.unwrap_err().kind(), &ErrorKind::TooManyRows);
+
+	assert_eq!(ctx.fetch_scalar::<i64>("SELECT COUNT(*) FROM user", vec![]).await?, 2);
+	assert_eq!(ctx.fetch_scalar::<Option<String>>("SELECT NULL", vec![]).await?, None);
+
+	let names = ctx.query_map("SELECT id, name FROM user ORDER BY id DESC", vec![], |row: Row| {
+		Ok(format!("{}:{}", row.get::<i64, _>("id"), row.try_get::<String, _>("name")?))
+	}).await?;
+	assert_eq!(names, vec!["2:bob".to_string(), "1:alice".to_string()]);
+	let err = ctx.query_map("SELECT id FROM user", vec![], |row: Row| row.try_get::<String, _>("name")).await.unwrap_err();
+	assert!(matches!(err.kind(), ErrorKind::InvalidArgument(_)));
+
+	// Values of the wrong type are reported.
+	let err = ctx.fetch_scalar::<i64>("SELECT 'x'", vec![]).await.unwrap_err();
+	assert_eq!(err.kind(), &ErrorKind::TypeMismatch);
+	let err = ctx.fetch_one::<(i64, String)>("SELECT id, NULL FROM user", vec![]).await.unwrap_err();
+	assert_eq!(err.kind(), &ErrorKind::TypeMismatch);
+	assert_eq!(ctx.fetch_one::<(i64, Option<String>)>("SELECT 1, NULL", vec![]).await?, (1, None));
+
+	// Transactions are connections too.
+	let mut trans = ctx.begin().await?;
+	trans.execute_sql::<()>("INSERT INTO user VALUES (3, 'carol')", vec![]).await?;
+	assert_eq!(trans.fetch_scalar::<i64>("SELECT COUNT(*) FROM user", vec![]).await?, 3);
+	assert_eq!(trans.fetch_one::<String>("SELECT name FROM user WHERE id = 3", vec![]).await?, "carol");
+	trans.rollback().await?;
+
+	assert_eq!(ctx.fetch_scalar::<i64>("SELECT COUNT(*) FROM user", vec![]).await?, 2);
+	Ok(())
+}
+
+#[async_std::test]
+async fn typed_queries() -> sql_connect::Result<()> {
+	use sql_connect::{
+		Row,
+		ErrorKind,
+		sqlite::Query
+	};
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("CREATE TABLE user (id INTEGER PRIMARY KEY, name TEXT)").await?;
+
+	let insert: Query<(i64, &str), ()> = ctx.prepare_query("INSERT INTO user VALUES (?, ?)")?;
+	insert.execute(&mut ctx, (1, "alice")).await?;
+	insert.execute(&mut ctx, (2, "bob")).await?;
+
+	let by_id: Query<(i64,), (i64, String)> = ctx.prepare_query("SELECT id, name FROM user WHERE id = ?")?;
+	assert_eq!(by_id.fetch_one(&mut ctx, (2,)).await?, (2, "bob".to_string()));
+	assert_eq!(by_id.fetch_optional(&mut ctx, (3,)).await?, None);
+
+	let all: Query<(), String> = ctx.prepare_query("SELECT name FROM user ORDER BY id")?;
+	assert_eq!(all.fetch_all(&mut ctx, ()).await?, vec!["alice".to_string(), "bob".to_string()]);
+
+	// Rows without a fixed number of columns are not checked.
+	let rows: Query<(), Row> = ctx.prepare_query("SELECT * FROM user")?;
+	assert_eq!(rows.fetch_all(&mut ctx, ()).await?.len(), 2);
+
+	let err = ctx.prepare_query::<(i64,), ()>("INSERT INTO user VALUES (?, ?)").err().unwrap();
+	assert_eq!(err.kind(), &ErrorKind::ParameterCountMismatch { expected: 1, found: 2 });
+	assert_eq!(err.to_string(), "expected 1 parameters, found 2 in statement");
+
+	let err = ctx.prepare_query::<(), (i64, String)>("SELECT * FROM user, user AS other").err().unwrap();
+	assert_eq!(err.kind(), &ErrorKind::ColumnCountMismatch { expected: 2, found: 4 });
+
+	// Statements without rows have no columns.
+	let err = ctx.prepare_query::<(i64, String), (i64, String)>("INSERT INTO user VALUES (?, ?)").err().unwrap();
+	assert_eq!(err.kind(), &ErrorKind::ColumnCountMismatch { expected: 2, found: 0 });
+	let err = ctx.prepare_query::<(), ()>("SELECT id, name FROM user").err().unwrap();
+	assert_eq!(err.kind(), &ErrorKind::ColumnCountMismatch { expected: 0, found: 2 });
+	let err = ctx.prepare_query::<(), ()>("SELECT id FROM user").err().unwrap();
+	assert_eq!(err.kind(), &ErrorKind::ColumnCountMismatch { expected: 0, found: 1 });
+
+	assert_eq!(ctx.prepare_query::<(), ()>(" ").err().unwrap().kind(), &ErrorKind::InvalidQuery);
+
+	// Typed queries work through transactions.
+	let mut trans = ctx.begin().await?;
+	insert.execute(&mut trans, (3, "carol")).await?;
+	assert_eq!(by_id.fetch_one(&mut trans, (3,)).await?.1, "carol");
+	trans.commit().await?;
+	Ok(())
+}
+
+#[cfg(feature = "macros")]
+#[async_std::test]
+async fn checked_queries() -> sql_connect::Result<()> {
+	use sql_connect::{
+		Value,
+		query
+	};
+
+	query!(pub InsertUser, "tests/schema", "INSERT INTO user (name, email, score) VALUES (?, ?, ?)");
+	query!(UserById, "tests/schema", "SELECT id, name, email, score, active FROM user WHERE id = ?");
+	query!(PostCount, "tests/schema", "SELECT user.name, COUNT(post.id) AS posts FROM user JOIN post ON post.author = user.id GROUP BY user.id ORDER BY user.id");
+	query!(UserPosts, "tests/schema", "SELECT user.name, post.title FROM user LEFT JOIN post ON post.author = user.id ORDER BY user.id, post.id");
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script(include_str!("schema/001_user.sql")).await?;
+	ctx.execute_script(include_str!("schema/002_post.sql")).await?;
+
+	let insert = InsertUser::prepare(&mut ctx)?;
+	insert.execute(&mut ctx, ["alice".into(), Value::Null, 1.5.into()]).await?;
+	insert.execute(&mut ctx, ["bob".into(), "bob@example.com".into(), Value::Null]).await?;
+	ctx.execute_script("INSERT INTO post (author, title) VALUES (1, 'a'), (1, 'b'), (2, 'c')").await?;
+
+	let by_id = UserById::prepare(&mut ctx)?;
+	let alice = by_id.fetch_one(&mut ctx, [1.into()]).await?;
+	assert_eq!(alice, UserById {
+		id: 1,
+		name: "alice".to_string(),
+		email: None,
+		score: Some(1.5),
+		active: Value::Integer(1)
+	});
+	assert_eq!(by_id.fetch_one(&mut ctx, [2.into()]).await?.email.as_deref(), Some("bob@example.com"));
+
+	// Declared types are not enforced by SQLite.
+	ctx.execute_script("UPDATE user SET score = 'high' WHERE id = 2").await?;
+	let err = by_id.fetch_one(&mut ctx, [2.into()]).await.unwrap_err();
+	assert_eq!(err.kind(), &sql_connect::ErrorKind::TypeMismatch);
+
+	let counts = PostCount::prepare(&mut ctx)?.fetch_all(&mut ctx, []).await?;
+	assert_eq!(counts.iter().map(|c| (c.name.as_str(), c.posts.clone())).collect::<Vec<_>>(), vec![("alice", Value::Integer(2)), ("bob", Value::Integer(1))]);
+	assert_eq!(PostCount::SQL, "SELECT user.name, COUNT(post.id) AS posts FROM user JOIN post ON post.author = user.id GROUP BY user.id ORDER BY user.id");
+
+	// Columns of outer joins are nullable, even if declared `NOT NULL`.
+	insert.execute(&mut ctx, ["carol".into(), Value::Null, Value::Null]).await?;
+	let posts = UserPosts::prepare(&mut ctx)?.fetch_all(&mut ctx, []).await?;
+	assert_eq!(posts.len(), 4);
+	assert_eq!(posts[3], UserPosts {
+		name: Some("carol".to_string()),
+		title: None
+	});
+	Ok(())
+}
+
+#[async_std::test]
+async fn script_with_trigger() -> sql_connect::Result<()> {
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	ctx.execute_script("
+		CREATE TABLE item (id INTEGER PRIMARY KEY, \"name;\" TEXT); -- items; with a comment
+		CREATE TABLE log (message TEXT);
+		/* Log insertions; twice. */
+		CREATE TRIGGER item_insert AFTER INSERT ON item BEGIN
+			INSERT INTO log VALUES ('inserted; ' || new.\"name;\");
+			INSERT INTO log VALUES (CASE WHEN new.id > 1 THEN 'many' ELSE 'first' END);
+		END;
+		INSERT INTO item (\"name;\") VALUES ('a'), ('b');
+	").await?;
+
+	let log: Vec<String> = ctx.fetch_all("SELECT message FROM log ORDER BY rowid", vec![]).await?;
+	assert_eq!(log, vec!["inserted; a", "first", "inserted; b", "many"]);
+
+	let statements = ctx.prepare_list("CREATE TEMP TRIGGER t AFTER DELETE ON item BEGIN DELETE FROM log; END; ;  DELETE FROM item")?;
+	assert_eq!(statements.len(), 2);
+	Ok(())
+}
+
+#[async_std::test]
+async fn statement_spans() -> sql_connect::Result<()> {
+	use sql_connect::{
+		ErrorKind,
+		sqlite::DatabaseError
+	};
+
+	let mut ctx = sql_connect::sqlite::Connection::new()?;
+	let sql = "  SELECT 1;\n-- comment; here\nSELECT 'a;b' ; ;\n\tSELECT 3  ";
+	let statements = ctx.prepare_list(sql)?;
+	let texts: Vec<_> = statements.iter().map(|s| &sql[s.span()]).collect();
+	assert_eq!(texts, vec!["SELECT 1;", "-- comment; here\nSELECT 'a;b' ;", "SELECT 3"]);
+
+	let statement = ctx.prepare(" SELECT 1; -- done")?.unwrap();
+	assert_eq!(statement.span(), 1..10);
+	assert!(ctx.prepare(" ; -- nothing")?.is_none());
+
+	let err = ctx.prepare("SELECT 1; SELECT 2").unwrap_err();
+	assert_eq!(err.kind(), &ErrorKind::MultipleStatements);
+	let err = ctx.prepare("CREATE TABLE a (x); INSERT INTO a VALUES (1)").unwrap_err();
+	assert_eq!(err.kind(), &ErrorKind::MultipleStatements);
+
+	// Statements are prepared once the previous ones are executed.
+	ctx.execute_script("CREATE TABLE a (x); INSERT INTO a VALUES (1); INSERT INTO a SELECT x + 1 FROM a").await?;
+	assert_eq!(ctx.fetch_scalar::<i64>("SELECT SUM(x) FROM a", vec![]).await?, 3);
+
+	// Statements preceding an error are executed.
+	let err = ctx.execute_script("INSERT INTO a VALUES (4); SELECT unknown FROM a; INSERT INTO a VALUES (6)").await.unwrap_err();
+	assert_eq!(ctx.fetch_all::<i64>("SELECT x FROM a ORDER BY x", vec![]).await?, vec![1, 2, 4]);
+
+	// Errors refer to the failing statement.
+	let details = std::error::Error::source(&err).unwrap().downcast_ref::<DatabaseError>().unwrap();
+	assert_eq!(details.sql(), Some("SELECT unknown FROM a"));
+	assert_eq!(details.offset(), Some(7));
+	let err = ctx.execute_script("SELECT 1;\n  SELECT * FORM a; SELECT 2").await.unwrap_err();
+	let details = std::error::Error::source(&err).unwrap().downcast_ref::<DatabaseError>().unwrap();
+	assert_eq!(details.sql(), Some("SELECT * FORM a"));
+	assert_eq!(details.offset(), Some(9));
+
+	// Scripts run in transactions are split by SQLite as well.
+	let mut trans = ctx.begin().await?;
+	trans.execute_script("CREATE TRIGGER a_insert AFTER INSERT ON a BEGIN INSERT INTO a VALUES (NULL); END; INSERT INTO a VALUES (7)").await?;
+	trans.commit().await?;
+	assert_eq!(ctx.fetch_scalar::<i64>("SELECT COUNT(*) FROM a", vec![]).await?, 5);
+	Ok(())
+}